@@ -0,0 +1,20 @@
+use crypto_rust::prelude::*;
+use crypto_rust::modes::cbc;
+
+
+fn main() {
+    let key = "yellow submarine".as_bytes();
+    let iv = [0; Aes128Cipher::BLOCK_SIZE];
+    let msg = "Attack at dawn!!".as_bytes();
+
+    let aes = Aes128Cipher::new(key).expect("Key size error");
+    let encrypted = cbc::encrypt(msg, &aes, &iv).expect("Invalid iv");
+    println!("Ciphertext: {}", to_hex(&encrypted));
+
+    let decrypted = cbc::decrypt(&encrypted, &aes, &iv).expect("Invalid iv");
+    println!("Plaintext: {}", String::from_utf8_lossy(&decrypted));
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
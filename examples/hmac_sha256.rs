@@ -0,0 +1,21 @@
+use crypto_rust::prelude::*;
+
+
+fn main() {
+    let key = "key".as_bytes();
+    let msg = "The quick brown fox jumps over the lazy dog".as_bytes();
+
+    let mut sha256 = Sha256::new();
+    sha256.update(msg);
+    println!("SHA256: {}", to_hex(&sha256.digest()));
+
+    let mut hmac = Hmac::<Sha256>::new(key).expect("Invalid key");
+    hmac.update(msg);
+    let signature = hmac.generate();
+    println!("HMAC-SHA256: {}", to_hex(&signature));
+    assert!(hmac.verify(msg, &signature).is_ok());
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,77 @@
+mod chacha20_poly1305;
+mod etm;
+mod gcm;
+
+use crate::error::{AeadError,InvalidDataLen,InvalidKeyLen};
+pub use chacha20_poly1305::{ChaCha20Poly1305,XChaCha20Poly1305};
+pub use etm::{EncryptThenMac,Cbc,Ctr,EtmMode,Aes128CbcHmacSha256,
+              Aes192CbcHmacSha384,Aes256CbcHmacSha512};
+pub use gcm::{Gcm,Aes128Gcm,Aes192Gcm,Aes256Gcm};
+
+
+// Authenticated encryption with associated data. Combined outputs are the
+// ciphertext followed by the tag. Implemented by Encrypt-then-MAC, GCM and
+// (X)ChaCha20-Poly1305; CCM, SIV, GCM-SIV, OCB and EAX are only available
+// through their own interfaces in modes
+pub trait Aead {
+    const KEY_SIZE: usize;
+    const NONCE_SIZE: usize;
+    const TAG_SIZE: usize;
+
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> where Self: Sized;
+
+    // Encrypts data in place and returns the tag
+    fn seal_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+        aad: &[u8]) -> Result<Vec<u8>, AeadError>;
+
+    // Data is only decrypted in place if the tag is valid
+    fn open_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+        aad: &[u8], tag: &[u8]) -> Result<(), AeadError>;
+
+    fn seal_detached(&self, data: &[u8], nonce: &[u8], aad: &[u8])
+            -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+        let mut encrypted = data.to_vec();
+        let tag = self.seal_in_place_detached(&mut encrypted, nonce, aad)?;
+        Ok((encrypted, tag))
+    }
+
+    fn open_detached(&self, data: &[u8], nonce: &[u8], aad: &[u8],
+            tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+        let mut decrypted = data.to_vec();
+        self.open_in_place_detached(&mut decrypted, nonce, aad, tag)?;
+        Ok(decrypted)
+    }
+
+    // Appends the tag to the encrypted data
+    fn seal_in_place(&self, data: &mut Vec<u8>, nonce: &[u8], aad: &[u8])
+            -> Result<(), AeadError> {
+        let tag = self.seal_in_place_detached(data, nonce, aad)?;
+        data.extend_from_slice(&tag);
+        Ok(())
+    }
+
+    // Removes the tag from the decrypted data
+    fn open_in_place(&self, data: &mut Vec<u8>, nonce: &[u8], aad: &[u8])
+            -> Result<(), AeadError> {
+        if data.len() < Self::TAG_SIZE {
+            return Err(InvalidDataLen.into());
+        }
+        let tag = data.split_off(data.len() - Self::TAG_SIZE);
+        self.open_in_place_detached(data, nonce, aad, &tag)
+            .inspect_err(|_| data.extend_from_slice(&tag))
+    }
+
+    fn seal(&self, data: &[u8], nonce: &[u8], aad: &[u8])
+            -> Result<Vec<u8>, AeadError> {
+        let mut encrypted = data.to_vec();
+        self.seal_in_place(&mut encrypted, nonce, aad)?;
+        Ok(encrypted)
+    }
+
+    fn open(&self, data: &[u8], nonce: &[u8], aad: &[u8])
+            -> Result<Vec<u8>, AeadError> {
+        let mut decrypted = data.to_vec();
+        self.open_in_place(&mut decrypted, nonce, aad)?;
+        Ok(decrypted)
+    }
+}
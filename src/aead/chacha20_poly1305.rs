@@ -0,0 +1,118 @@
+use crate::cipher::{ChaCha20,XChaCha20};
+use crate::error::{AeadError,InvalidKeyLen};
+use crate::modes::chacha20_poly1305 as mode;
+use super::Aead;
+
+
+// RFC 8439 AEAD_CHACHA20_POLY1305
+pub struct ChaCha20Poly1305 {
+    cipher: ChaCha20,
+}
+
+// Same construction with 192 bit nonces, for randomly generated nonces
+pub struct XChaCha20Poly1305 {
+    cipher: XChaCha20,
+}
+
+impl Aead for ChaCha20Poly1305 {
+    const KEY_SIZE: usize = mode::KEY_SIZE;
+    const NONCE_SIZE: usize = mode::NONCE_SIZE;
+    const TAG_SIZE: usize = mode::TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        Ok(Self { cipher: ChaCha20::new(key)? })
+    }
+
+    fn seal_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8]) -> Result<Vec<u8>, AeadError> {
+        let (encrypted, tag) = mode::encrypt(data, &self.cipher, nonce, aad)?;
+        *data = encrypted;
+        Ok(tag)
+    }
+
+    fn open_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8], tag: &[u8]) -> Result<(), AeadError> {
+        *data = mode::decrypt(data, &self.cipher, nonce, aad, tag)?;
+        Ok(())
+    }
+}
+
+impl Aead for XChaCha20Poly1305 {
+    const KEY_SIZE: usize = mode::KEY_SIZE;
+    const NONCE_SIZE: usize = mode::XNONCE_SIZE;
+    const TAG_SIZE: usize = mode::TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        Ok(Self { cipher: XChaCha20::new(key)? })
+    }
+
+    fn seal_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8]) -> Result<Vec<u8>, AeadError> {
+        let (encrypted, tag) = mode::encrypt_x(data, &self.cipher, nonce,
+                                               aad)?;
+        *data = encrypted;
+        Ok(tag)
+    }
+
+    fn open_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8], tag: &[u8]) -> Result<(), AeadError> {
+        *data = mode::decrypt_x(data, &self.cipher, nonce, aad, tag)?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::aead::{Aead,ChaCha20Poly1305,XChaCha20Poly1305};
+    use crate::error::AeadError;
+    use crate::utils::decode_hex;
+
+    const MSG: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could \
+                         offer you only one tip for the future, sunscreen \
+                         would be it.";
+    const AAD: &str = "50515253c0c1c2c3c4c5c6c7";
+
+    fn test_aead<A: Aead>(key: &str, nonce: &str, expected: &str) {
+        let aead = A::new(&decode_hex(key).unwrap()).unwrap();
+        let nonce = decode_hex(nonce).unwrap();
+        let aad = decode_hex(AAD).unwrap();
+
+        let sealed = aead.seal(MSG, &nonce, &aad).unwrap();
+        assert_eq!(sealed, decode_hex(expected).unwrap());
+        assert_eq!(aead.open(&sealed, &nonce, &aad).unwrap(), MSG);
+
+        let mut tampered = sealed.clone();
+        tampered[0] ^= 1;
+        let result = aead.open(&tampered, &nonce, &aad);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = aead.seal(MSG, &nonce[1..], &aad);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+    }
+
+    #[test]
+    fn chacha20_poly1305() {
+        // RFC 8439 section 2.8.2
+        test_aead::<ChaCha20Poly1305>(
+            "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            "070000004041424344454647",
+            "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+             3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+             92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+             3ff4def08e4b7a9de576d26586cec64b61161ae10b594f09e26a7e902ecbd060\
+             0691");
+    }
+
+    #[test]
+    fn xchacha20_poly1305() {
+        // draft-irtf-cfrg-xchacha A.3.1
+        test_aead::<XChaCha20Poly1305>(
+            "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            "404142434445464748494a4b4c4d4e4f5051525354555657",
+            "bd6d179d3e83d43b9576579493c0e939572a1700252bfaccbed2902c21396cbb\
+             731c7f1b0b4aa6440bf3a82f4eda7e39ae64c6708c54c216cb96b72e1213b452\
+             2f8c9ba40db5d945b11b69b982c1bb9e3f3fac2bc369488f76b2383565d3fff9\
+             21f9664c97637da9768812f615c68b13b52e\
+             c0875924c1c7987947deafd8780acf49");
+    }
+}
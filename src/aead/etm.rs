@@ -0,0 +1,241 @@
+use std::marker::PhantomData;
+
+use crate::cipher::{Cipher,Aes128Cipher,Aes192Cipher,Aes256Cipher};
+use crate::error::{AeadError,AuthenticationFailed,InvalidIvLen,InvalidKeyLen,
+                   InvalidTagLen,ModeError};
+use crate::hash::{Hash,Sha256,Sha384,Sha512};
+use crate::mac::{Mac,Hmac};
+use crate::modes::{cbc,ctr};
+use crate::modes::padding::{Padding,Pkcs7};
+use crate::utils;
+use super::Aead;
+
+
+// Mode used for the encryption part of Encrypt-then-MAC, in place
+pub trait EtmMode<C> where C: Cipher {
+    const NONCE_SIZE: usize;
+
+    fn encrypt(data: &mut Vec<u8>, cipher: &C, nonce: &[u8])
+        -> Result<(), ModeError>;
+
+    fn decrypt(data: &mut Vec<u8>, cipher: &C, nonce: &[u8])
+        -> Result<(), ModeError>;
+}
+
+// CBC with PKCS#7 padding, the nonce is the IV
+pub struct Cbc;
+
+// CTR with a half block nonce
+pub struct Ctr;
+
+impl<C> EtmMode<C> for Cbc where C: Cipher {
+    const NONCE_SIZE: usize = C::BLOCK_SIZE;
+
+    fn encrypt(data: &mut Vec<u8>, cipher: &C, nonce: &[u8])
+            -> Result<(), ModeError> {
+        *data = Pkcs7::pad(data, C::BLOCK_SIZE)?;
+        cbc::encrypt_in_place(data, cipher, nonce)
+    }
+
+    fn decrypt(data: &mut Vec<u8>, cipher: &C, nonce: &[u8])
+            -> Result<(), ModeError> {
+        cbc::decrypt_in_place(data, cipher, nonce)?;
+        let unpadded_len = Pkcs7::unpad(data, C::BLOCK_SIZE)?.len();
+        data.truncate(unpadded_len);
+        Ok(())
+    }
+}
+
+impl<C> EtmMode<C> for Ctr where C: Cipher {
+    const NONCE_SIZE: usize = C::BLOCK_SIZE / 2;
+
+    fn encrypt(data: &mut Vec<u8>, cipher: &C, nonce: &[u8])
+            -> Result<(), ModeError> {
+        Ok(ctr::apply_keystream(data, cipher, nonce)?)
+    }
+
+    fn decrypt(data: &mut Vec<u8>, cipher: &C, nonce: &[u8])
+            -> Result<(), ModeError> {
+        Ok(ctr::apply_keystream(data, cipher, nonce)?)
+    }
+}
+
+
+// RFC 7518 5.2 composition, the key is the MAC key followed by the
+// encryption key. The tag is the first half of
+// HMAC(AAD || nonce || ciphertext || AAD length in bits)
+pub struct EncryptThenMac<C, H, M> where C: Cipher, H: Hash, M: EtmMode<C> {
+    cipher: C,
+    hmac: Hmac<H>,
+    mode: PhantomData<M>,
+}
+
+// JOSE AES_CBC_HMAC_SHA2 algorithms
+pub type Aes128CbcHmacSha256 = EncryptThenMac<Aes128Cipher, Sha256, Cbc>;
+pub type Aes192CbcHmacSha384 = EncryptThenMac<Aes192Cipher, Sha384, Cbc>;
+pub type Aes256CbcHmacSha512 = EncryptThenMac<Aes256Cipher, Sha512, Cbc>;
+
+impl<C, H, M> EncryptThenMac<C, H, M>
+        where C: Cipher, H: Hash, M: EtmMode<C> {
+    fn compute_tag(&self, ciphertext: &[u8], nonce: &[u8], aad: &[u8])
+            -> Vec<u8> {
+        let mut hmac = self.hmac.clone();
+        hmac.update(aad);
+        hmac.update(nonce);
+        hmac.update(ciphertext);
+        hmac.update(&(aad.len() as u64 * 8).to_be_bytes());
+        let mut tag = hmac.generate();
+        tag.truncate(Self::TAG_SIZE);
+        tag
+    }
+}
+
+impl<C, H, M> Aead for EncryptThenMac<C, H, M>
+        where C: Cipher, H: Hash, M: EtmMode<C> {
+    const KEY_SIZE: usize = 2 * C::KEY_SIZE;
+    const NONCE_SIZE: usize = M::NONCE_SIZE;
+    const TAG_SIZE: usize = H::DIGEST_SIZE / 2;
+
+    // Both halves have the same size
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        if !key.len().is_multiple_of(2) {
+            return Err(InvalidKeyLen);
+        }
+        let (mac_key, key) = key.split_at(key.len() / 2);
+        let cipher = C::new(key)?;
+        let hmac = Hmac::<H>::new(mac_key)?;
+        Ok(Self { cipher, hmac, mode: PhantomData })
+    }
+
+    fn seal_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if nonce.len() != Self::NONCE_SIZE {
+            return Err(InvalidIvLen.into());
+        }
+        M::encrypt(data, &self.cipher, nonce).map_err(aead_error)?;
+        Ok(self.compute_tag(data, nonce, aad))
+    }
+
+    fn open_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8], tag: &[u8]) -> Result<(), AeadError> {
+        if nonce.len() != Self::NONCE_SIZE {
+            return Err(InvalidIvLen.into());
+        }
+        if tag.len() != Self::TAG_SIZE {
+            return Err(InvalidTagLen.into());
+        }
+        let expected_tag = self.compute_tag(data, nonce, aad);
+        if !utils::constant_time_eq(&expected_tag, tag) {
+            return Err(AuthenticationFailed.into());
+        }
+        M::decrypt(data, &self.cipher, nonce).map_err(aead_error)?;
+        Ok(())
+    }
+}
+
+
+// Padding errors can only happen after the tag was verified, they are
+// reported as authentication failures to avoid padding oracles
+fn aead_error(e: ModeError) -> AeadError {
+    match e {
+        ModeError::InvalidIvLen(e) => e.into(),
+        ModeError::InvalidDataLen(e) => e.into(),
+        ModeError::InvalidPadding(_) => AuthenticationFailed.into(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::aead::{Aead,EncryptThenMac,Ctr,Aes128CbcHmacSha256,
+                      Aes192CbcHmacSha384,Aes256CbcHmacSha512};
+    use crate::cipher::Aes128Cipher;
+    use crate::error::AeadError;
+    use crate::hash::Sha256;
+    use crate::utils::decode_hex;
+
+    // RFC 7518 Appendix B test cases
+    const MSG: &str = "41206369706865722073797374656d206d757374206e6f742062\
+                       6520726571756972656420746f206265207365637265742c2061\
+                       6e64206974206d7573742062652061626c6520746f2066616c6c\
+                       20696e746f207468652068616e6473206f662074686520656e65\
+                       6d7920776974686f757420696e636f6e76656e69656e6365";
+    const IV: &str = "1af38c2dc2b96ffdd86694092341bc04";
+    const AAD: &str = "546865207365636f6e64207072696e6369706c65206f66204175\
+                       6775737465204b6572636b686f666673";
+
+    fn test_jose<A: Aead>(expected: &str, expected_tag: &str) {
+        let key: Vec<u8> = (0..A::KEY_SIZE as u8).collect();
+        let aead = A::new(&key).unwrap();
+        let msg = decode_hex(MSG).unwrap();
+        let iv = decode_hex(IV).unwrap();
+        let aad = decode_hex(AAD).unwrap();
+
+        let (encrypted, tag) = aead.seal_detached(&msg, &iv, &aad).unwrap();
+        assert_eq!(encrypted, decode_hex(expected).unwrap());
+        assert_eq!(tag, decode_hex(expected_tag).unwrap());
+        let decrypted = aead.open_detached(&encrypted, &iv, &aad, &tag)
+            .unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn aes_128_cbc_hmac_sha256() {
+        test_jose::<Aes128CbcHmacSha256>(
+            "c80edfa32ddf39d5ef00c0b468834279a2e46a1b8049f792f76bfe54b903a9c9\
+             a94ac9b47ad2655c5f10f9aef71427e2fc6f9b3f399a221489f16362c7032336\
+             09d45ac69864e3321cf82935ac4096c86e133314c54019e8ca7980dfa4b9cf1b\
+             384c486f3a54c51078158ee5d79de59fbd34d848b3d69550a67646344427ade5\
+             4b8851ffb598f7f80074b9473c82e2db",
+            "652c3fa36b0a7c5b3219fab3a30bc1c4");
+    }
+
+    #[test]
+    fn aes_192_cbc_hmac_sha384() {
+        test_jose::<Aes192CbcHmacSha384>(
+            "ea65da6b59e61edb419be62d19712ae5d303eeb50052d0dfd6697f77224c8edb\
+             000d279bdc14c1072654bd30944230c657bed4ca0c9f4a8466f22b226d174621\
+             4bf8cfc2400add9f5126e479663fc90b3bed787a2f0ffcbf3904be2a641d5c21\
+             05bfe591bae23b1d7449e532eef60a9ac8bb6c6b01d35d49787bcd57ef484927\
+             f280adc91ac0c4e79c7b11efc60054e3",
+            "8490ac0e58949bfe51875d733f93ac2075168039ccc733d7");
+    }
+
+    #[test]
+    fn aes_256_cbc_hmac_sha512() {
+        test_jose::<Aes256CbcHmacSha512>(
+            "4affaaadb78c31c5da4b1b590d10ffbd3dd8d5d302423526912da037ecbcc7bd\
+             822c301dd67c373bccb584ad3e9279c2e6d12a1374b77f077553df829410446b\
+             36ebd97066296ae6427ea75c2e0846a11a09ccf5370dc80bfecbad28c73f09b3\
+             a3b75e662a2594410ae496b2e2e6609e31e6e02cc837f053d21f37ff4f51950b\
+             be2638d09dd7a4930930806d0703b1f6",
+            "4dd3b4c088a7f45c216839645b2012bf2e6269a8c56a816dbc1b267761955bc5");
+    }
+
+    #[test]
+    fn encrypt_then_mac_ctr() {
+        type Aes128CtrHmacSha256 = EncryptThenMac<Aes128Cipher, Sha256, Ctr>;
+        assert_eq!(Aes128CtrHmacSha256::KEY_SIZE, 32);
+        assert_eq!(Aes128CtrHmacSha256::NONCE_SIZE, 8);
+        assert_eq!(Aes128CtrHmacSha256::TAG_SIZE, 16);
+
+        let aead = Aes128CtrHmacSha256::new(&[0x42; 32]).unwrap();
+        let mut data = b"Attack at dawn!".to_vec();
+        aead.seal_in_place(&mut data, &[0; 8], b"header").unwrap();
+        assert_eq!(data.len(), 15 + 16);
+        let sealed = data.clone();
+        aead.open_in_place(&mut data, &[0; 8], b"header").unwrap();
+        assert_eq!(data, b"Attack at dawn!");
+
+        let mut data = sealed.clone();
+        let result = aead.open_in_place(&mut data, &[1; 8], b"header");
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        assert_eq!(data, sealed);
+        let result = aead.open(&sealed, &[0; 8], b"other");
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = aead.open(&sealed[..15], &[0; 8], b"header");
+        assert!(matches!(result, Err(AeadError::InvalidDataLen(_))));
+        let result = aead.seal(b"", &[0; 16], b"");
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+    }
+}
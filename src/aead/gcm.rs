@@ -0,0 +1,82 @@
+use crate::cipher::{Cipher,Aes128Cipher,Aes192Cipher,Aes256Cipher};
+use crate::error::{AeadError,InvalidIvLen,InvalidKeyLen,InvalidTagLen};
+use crate::modes::gcm;
+use super::Aead;
+
+
+// GCM with 96 bit nonces and full size tags, other sizes are available
+// through modes::gcm
+pub struct Gcm<C> where C: Cipher<Block = [u8; gcm::BLOCK_SIZE]> {
+    cipher: C,
+}
+
+pub type Aes128Gcm = Gcm<Aes128Cipher>;
+pub type Aes192Gcm = Gcm<Aes192Cipher>;
+pub type Aes256Gcm = Gcm<Aes256Cipher>;
+
+impl<C> Aead for Gcm<C> where C: Cipher<Block = [u8; gcm::BLOCK_SIZE]> {
+    const KEY_SIZE: usize = C::KEY_SIZE;
+    const NONCE_SIZE: usize = gcm::NONCE_SIZE;
+    const TAG_SIZE: usize = gcm::TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        Ok(Self { cipher: C::new(key)? })
+    }
+
+    fn seal_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if nonce.len() != Self::NONCE_SIZE {
+            return Err(InvalidIvLen.into());
+        }
+        let (encrypted, tag) = gcm::encrypt(data, &self.cipher, nonce, aad,
+                                            Self::TAG_SIZE)?;
+        *data = encrypted;
+        Ok(tag)
+    }
+
+    fn open_in_place_detached(&self, data: &mut Vec<u8>, nonce: &[u8],
+            aad: &[u8], tag: &[u8]) -> Result<(), AeadError> {
+        if nonce.len() != Self::NONCE_SIZE {
+            return Err(InvalidIvLen.into());
+        }
+        if tag.len() != Self::TAG_SIZE {
+            return Err(InvalidTagLen.into());
+        }
+        *data = gcm::decrypt(data, &self.cipher, nonce, aad, tag)?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::aead::{Aead,Aes128Gcm};
+    use crate::error::AeadError;
+    use crate::utils::decode_hex;
+
+    #[test]
+    fn aes_128_gcm() {
+        // GCM specification test case 4
+        let key = decode_hex("feffe9928665731c6d6a8f9467308308").unwrap();
+        let nonce = decode_hex("cafebabefacedbaddecaf888").unwrap();
+        let msg = decode_hex("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da\
+                              2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525\
+                              b16aedf5aa0de657ba637b39").unwrap();
+        let aad = decode_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2")
+            .unwrap();
+        let expected = decode_hex("42831ec2217774244b7221b784d0d49ce3aa212f2c\
+                                   02a4e035c17e2329aca12e21d514b25466931c7d8f\
+                                   6a5aac84aa051ba30b396a0aac973d58e091\
+                                   5bc94fbc3221a5db94fae95ae7121a47").unwrap();
+
+        let aead = Aes128Gcm::new(&key).unwrap();
+        let sealed = aead.seal(&msg, &nonce, &aad).unwrap();
+        assert_eq!(sealed, expected);
+        assert_eq!(aead.open(&sealed, &nonce, &aad).unwrap(), msg);
+
+        let result = aead.open(&sealed, &nonce, b"other");
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = aead.seal(&msg, &nonce[..8], &aad);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+    }
+}
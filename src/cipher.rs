@@ -1,21 +1,95 @@
-mod aes_128;
-mod aes_128_constants;
+mod aes;
+mod aes_bitsliced;
+mod aes_constants;
+#[cfg(target_arch = "x86_64")]
+mod aes_ni;
+mod aes_ttable;
+mod chacha20;
 mod des;
 mod des_constants;
+mod triple_des;
 
 use crate::error::{InvalidKeyLen,InvalidDataLen};
-pub use aes_128::Aes128Cipher;
+pub use aes::{Aes128Cipher,Aes192Cipher,Aes256Cipher,AesBackend};
+pub use chacha20::{ChaCha20,XChaCha20};
 pub use des::DesCipher;
+pub use triple_des::TripleDesCipher;
 
 
+// Byte arrays used as cipher blocks. The size is part of the type, so byte
+// buffers can be viewed as whole blocks without checking it at runtime
+pub trait BlockArray: Copy + Default + AsRef<[u8]> + AsMut<[u8]> {
+    const SIZE: usize;
+
+    // Whole blocks at the start of data and the remaining bytes
+    fn split(data: &[u8]) -> (&[Self], &[u8]);
+
+    fn split_mut(data: &mut [u8]) -> (&mut [Self], &mut [u8]);
+}
+
+impl<const N: usize> BlockArray for [u8; N] where Self: Default {
+    const SIZE: usize = N;
+
+    fn split(data: &[u8]) -> (&[Self], &[u8]) {
+        data.as_chunks()
+    }
+
+    fn split_mut(data: &mut [u8]) -> (&mut [Self], &mut [u8]) {
+        data.as_chunks_mut()
+    }
+}
+
+
+// Block ciphers work in place on fixed size blocks without allocating, the
+// slice based encrypt/decrypt are kept for convenience
 pub trait Cipher {
-    const BLOCK_SIZE: usize;
+    // Given by the Block type
+    const BLOCK_SIZE: usize = <Self::Block as BlockArray>::SIZE;
+    // Default key size, some ciphers also accept other sizes
+    const KEY_SIZE: usize;
+
+    type Block: BlockArray;
 
     fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> where Self: Sized;
 
-    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, InvalidDataLen>;
+    fn encrypt_block(&self, block: &mut Self::Block);
+
+    fn decrypt_block(&self, block: &mut Self::Block);
+
+    // Implementations can override these to process several blocks at once
+    fn encrypt_blocks(&self, blocks: &mut [Self::Block]) {
+        for block in blocks {
+            self.encrypt_block(block);
+        }
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [Self::Block]) {
+        for block in blocks {
+            self.decrypt_block(block);
+        }
+    }
 
-    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, InvalidDataLen>;
+    fn to_block(data: &[u8]) -> Result<Self::Block, InvalidDataLen>
+            where Self: Sized {
+        match Self::Block::split(data) {
+            ([block], []) => Ok(*block),
+            _ => Err(InvalidDataLen),
+        }
+    }
+
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, InvalidDataLen>
+            where Self: Sized {
+        let mut block = Self::to_block(data)?;
+        self.encrypt_block(&mut block);
+        Ok(block.as_ref().to_vec())
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, InvalidDataLen>
+            where Self: Sized {
+        let mut block = Self::to_block(data)?;
+        self.decrypt_block(&mut block);
+        Ok(block.as_ref().to_vec())
+    }
 
     fn block_size(&self) -> usize {
         Self::BLOCK_SIZE
@@ -0,0 +1,460 @@
+use std::slice;
+
+use crate::error::InvalidKeyLen;
+use crate::utils;
+use super::aes_constants as constants;
+use super::{aes_bitsliced,aes_ttable};
+#[cfg(target_arch = "x86_64")]
+use super::aes_ni;
+use super::Cipher;
+
+
+// Implementation used for the block operations, the reference one follows
+// FIPS-197 step by step while the T-table one merges SubBytes, ShiftRows and
+// MixColumns into 32 bit table lookups. Both index tables with secret data,
+// the bitsliced one uses no tables and runs in constant time. AES-NI is
+// detected at runtime, falling back to the T-table implementation on CPUs
+// without it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AesBackend {
+    Reference,
+    TTable,
+    Bitsliced,
+    #[default]
+    AesNi,
+}
+
+enum RoundKeys {
+    Reference(Vec<[u8; constants::BLOCK_SIZE]>),
+    TTable(aes_ttable::RoundKeys),
+    Bitsliced(aes_bitsliced::RoundKeys),
+    #[cfg(target_arch = "x86_64")]
+    AesNi(aes_ni::RoundKeys),
+}
+
+impl RoundKeys {
+    fn hardware(keys: &[[u8; constants::BLOCK_SIZE]]) -> Self {
+        #[cfg(target_arch = "x86_64")]
+        if let Some(keys) = aes_ni::RoundKeys::new(keys) {
+            return Self::AesNi(keys);
+        }
+        Self::TTable(aes_ttable::RoundKeys::new(keys))
+    }
+}
+
+macro_rules! aes_impl {
+    ( $name:ident, $key_s:expr, $rounds:expr ) =>
+    {
+        pub struct $name {
+            keys: RoundKeys,
+        }
+
+        impl $name {
+            pub fn with_backend(key: &[u8], backend: AesBackend)
+                    -> Result<Self, InvalidKeyLen> {
+                if key.len() != Self::KEY_SIZE {
+                    return Err(InvalidKeyLen);
+                }
+
+                let keys = match backend {
+                    AesBackend::Reference => {
+                        RoundKeys::Reference(key_expansion(key, $rounds,
+                                                           sub_word))
+                    },
+                    AesBackend::TTable => {
+                        let keys = key_expansion(key, $rounds, sub_word);
+                        RoundKeys::TTable(aes_ttable::RoundKeys::new(&keys))
+                    },
+                    AesBackend::Bitsliced => {
+                        let keys = key_expansion(key, $rounds,
+                                                 aes_bitsliced::sub_word);
+                        RoundKeys::Bitsliced(
+                            aes_bitsliced::RoundKeys::new(&keys))
+                    },
+                    AesBackend::AesNi => {
+                        let keys = key_expansion(key, $rounds, sub_word);
+                        RoundKeys::hardware(&keys)
+                    },
+                };
+                Ok(Self { keys })
+            }
+
+            pub fn backend(&self) -> AesBackend {
+                match self.keys {
+                    RoundKeys::Reference(_) => AesBackend::Reference,
+                    RoundKeys::TTable(_) => AesBackend::TTable,
+                    RoundKeys::Bitsliced(_) => AesBackend::Bitsliced,
+                    #[cfg(target_arch = "x86_64")]
+                    RoundKeys::AesNi(_) => AesBackend::AesNi,
+                }
+            }
+        }
+
+        impl Cipher for $name {
+            const KEY_SIZE: usize = $key_s;
+
+            type Block = [u8; constants::BLOCK_SIZE];
+
+            fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+                Self::with_backend(key, AesBackend::default())
+            }
+
+            fn encrypt_block(&self, block: &mut Self::Block) {
+                match &self.keys {
+                    RoundKeys::Reference(keys) => {
+                        *block = aes_encrypt(block, keys);
+                    },
+                    RoundKeys::TTable(keys) => {
+                        *block = aes_ttable::encrypt(block, keys);
+                    },
+                    RoundKeys::Bitsliced(keys) => {
+                        aes_bitsliced::encrypt(slice::from_mut(block), keys);
+                    },
+                    #[cfg(target_arch = "x86_64")]
+                    RoundKeys::AesNi(keys) => {
+                        aes_ni::encrypt(slice::from_mut(block), keys);
+                    },
+                }
+            }
+
+            fn decrypt_block(&self, block: &mut Self::Block) {
+                match &self.keys {
+                    RoundKeys::Reference(keys) => {
+                        *block = aes_decrypt(block, keys);
+                    },
+                    RoundKeys::TTable(keys) => {
+                        *block = aes_ttable::decrypt(block, keys);
+                    },
+                    RoundKeys::Bitsliced(keys) => {
+                        aes_bitsliced::decrypt(slice::from_mut(block), keys);
+                    },
+                    #[cfg(target_arch = "x86_64")]
+                    RoundKeys::AesNi(keys) => {
+                        aes_ni::decrypt(slice::from_mut(block), keys);
+                    },
+                }
+            }
+
+            // The bitsliced and AES-NI implementations encrypt several
+            // blocks at once
+            fn encrypt_blocks(&self, blocks: &mut [Self::Block]) {
+                match &self.keys {
+                    RoundKeys::Bitsliced(keys) => {
+                        for group in blocks.chunks_mut(aes_bitsliced::BLOCKS) {
+                            aes_bitsliced::encrypt(group, keys);
+                        }
+                    },
+                    #[cfg(target_arch = "x86_64")]
+                    RoundKeys::AesNi(keys) => aes_ni::encrypt(blocks, keys),
+                    _ => blocks.iter_mut().for_each(|b| self.encrypt_block(b)),
+                }
+            }
+
+            fn decrypt_blocks(&self, blocks: &mut [Self::Block]) {
+                match &self.keys {
+                    RoundKeys::Bitsliced(keys) => {
+                        for group in blocks.chunks_mut(aes_bitsliced::BLOCKS) {
+                            aes_bitsliced::decrypt(group, keys);
+                        }
+                    },
+                    #[cfg(target_arch = "x86_64")]
+                    RoundKeys::AesNi(keys) => aes_ni::decrypt(blocks, keys),
+                    _ => blocks.iter_mut().for_each(|b| self.decrypt_block(b)),
+                }
+            }
+        }
+    }
+}
+
+
+aes_impl!(Aes128Cipher, constants::AES128_KEY_SIZE, constants::AES128_ROUNDS);
+aes_impl!(Aes192Cipher, constants::AES192_KEY_SIZE, constants::AES192_ROUNDS);
+aes_impl!(Aes256Cipher, constants::AES256_KEY_SIZE, constants::AES256_ROUNDS);
+
+
+fn aes_encrypt(plaintext: &[u8; 16], keys: &[[u8; 16]]) -> [u8; 16] {
+    let rounds = keys.len() - 1;
+    let mut state: [u8; 16] = *plaintext;
+
+    add_round_key(&mut state, &keys[0]);
+
+    for key in &keys[1..rounds] {
+        sub_bytes(&mut state, &constants::S_BOX);
+        shift_rows(&mut state, constants::ShiftRows::Left);
+        mix_columns(&mut state, &constants::MC_MATRIX);
+        add_round_key(&mut state, key);
+    }
+    // Last round
+    sub_bytes(&mut state, &constants::S_BOX);
+    shift_rows(&mut state, constants::ShiftRows::Left);
+    add_round_key(&mut state, &keys[rounds]);
+
+    state
+}
+
+fn aes_decrypt(ciphertext: &[u8; 16], keys: &[[u8; 16]]) -> [u8; 16] {
+    let rounds = keys.len() - 1;
+    let mut state: [u8; 16] = *ciphertext;
+
+    add_round_key(&mut state, &keys[rounds]);
+
+    for key in keys[1..rounds].iter().rev() {
+        shift_rows(&mut state, constants::ShiftRows::Right);
+        sub_bytes(&mut state, &constants::INV_S_BOX);
+        add_round_key(&mut state, key);
+        mix_columns(&mut state, &constants::INV_MC_MATRIX);
+    }
+
+    shift_rows(&mut state, constants::ShiftRows::Right);
+    sub_bytes(&mut state, &constants::INV_S_BOX);
+    add_round_key(&mut state, &keys[0]);
+
+    state
+}
+
+
+fn sub_bytes(state: &mut [u8], s_box: &[u8; 256]) {
+    for x in state {
+        *x = s_box[*x as usize];
+    }
+}
+
+
+fn shift_rows(state: &mut [u8; 16], rot: constants::ShiftRows) {
+    let orig = *state;
+
+    for i in 1..4 { // First word does not rotate
+        for j in 0..4 {
+            match rot {
+                constants::ShiftRows::Left => {
+                    state[i + 4*j] = orig[(i + 4*(j+i)) % 16];
+                },
+                constants::ShiftRows::Right => {
+                    state[(i + 4*(j+i)) % 16] = orig[i + 4*j]
+                }
+            }
+        }
+    }
+}
+
+pub(super) const fn galois_mult(mut a: u8, mut b: u8) -> u8 {
+    let mut p: u8 = 0;
+    while b != 0 {
+        if b & 0x01 != 0 {
+            p ^= a;
+        }
+        if a & 0x80 != 0 {
+            a = (a << 1) ^ 0x1B;
+        } else {
+            a <<= 1;
+        }
+        b >>= 1;
+    }
+    p
+}
+
+fn mix_columns(state: &mut [u8; 16], matrix: &[[u8; 4]; 4]) {
+    let orig = *state;
+
+    for i in 0..4 {
+        for j in 0..4 {
+            state[i*4 + j] = galois_mult(matrix[j][0], orig[i*4])
+                           ^ galois_mult(matrix[j][1], orig[i*4 + 1])
+                           ^ galois_mult(matrix[j][2], orig[i*4 + 2])
+                           ^ galois_mult(matrix[j][3], orig[i*4 + 3]);
+        }
+    }
+}
+
+fn add_round_key(state: &mut [u8; 16], key: &[u8; 16]) {
+    utils::xor_slice(state.as_mut_slice(), key.as_slice());
+}
+
+fn sub_word(word: &mut [u8]) {
+    sub_bytes(word, &constants::S_BOX);
+}
+
+fn key_expansion(key: &[u8], rounds: usize, sub_word: fn(&mut [u8]))
+        -> Vec<[u8; 16]> {
+    // Key schedule works with 4 byte words, the key is Nk words long
+    let nk = key.len() / 4;
+    let mut words: Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds+1));
+    for word in key.chunks_exact(4) {
+        words.push(word.try_into().unwrap());
+    }
+
+    for i in nk..4*(rounds+1) {
+        let mut word = words[i-1];
+        if i % nk == 0 {
+            // Rotate, Sub, round constant
+            word.rotate_left(1);
+            sub_word(&mut word);
+            word[0] ^= constants::R_CONST[i/nk - 1];
+        } else if nk > 6 && i % nk == 4 {
+            // AES-256 also substitutes the middle word
+            sub_word(&mut word);
+        }
+        utils::xor_slice(&mut word, &words[i-nk]);
+        words.push(word);
+    }
+
+    let mut keys: Vec<[u8; 16]> = Vec::with_capacity(rounds+1);
+    for round_words in words.chunks_exact(4) {
+        keys.push(round_words.concat().try_into().unwrap());
+    }
+    keys
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{Aes128Cipher,Aes192Cipher,Aes256Cipher,AesBackend,Cipher};
+    use crate::utils::decode_hex;
+
+    const KEY: &[u8] = "yellow submarine".as_bytes();
+    const MSG: &[u8] = "Attack at dawn!!".as_bytes();
+
+    // FIPS-197 appendix C
+    const FIPS_KEY: &str = "000102030405060708090a0b0c0d0e0f\
+                            101112131415161718191a1b1c1d1e1f";
+    const FIPS_MSG: &str = "00112233445566778899aabbccddeeff";
+
+    const BACKENDS: [AesBackend; 4] = [AesBackend::Reference,
+                                       AesBackend::TTable,
+                                       AesBackend::Bitsliced,
+                                       AesBackend::AesNi];
+
+    // AES-NI falls back to T-tables on CPUs without it
+    fn resolved(backend: AesBackend) -> AesBackend {
+        #[cfg(target_arch = "x86_64")]
+        let aes_ni = is_x86_feature_detected!("aes");
+        #[cfg(not(target_arch = "x86_64"))]
+        let aes_ni = false;
+        if backend == AesBackend::AesNi && !aes_ni {
+            AesBackend::TTable
+        } else {
+            backend
+        }
+    }
+
+    fn test_aes(aes: &impl Cipher, msg: &[u8], expected: &[u8]) {
+        let encrypted = aes.encrypt(msg).unwrap();
+        assert_eq!(encrypted, expected);
+        let decrypted = aes.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn aes() {
+        let expected = decode_hex("9012932cf5a953b0e97ff4e21a8ea9df").unwrap();
+        let aes = Aes128Cipher::new(KEY).expect("Key size error");
+        let encrypted = aes.encrypt(MSG).unwrap();
+        assert_eq!(encrypted, expected);
+        let decrypted = aes.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted, MSG);
+    }
+
+    #[test]
+    fn aes_128() {
+        let key = decode_hex(&FIPS_KEY[..32]).unwrap();
+        let msg = decode_hex(FIPS_MSG).unwrap();
+        let expected = decode_hex("69c4e0d86a7b0430d8cdb78070b4c55a").unwrap();
+        for backend in BACKENDS {
+            let aes = Aes128Cipher::with_backend(&key, backend)
+                .expect("Key size error");
+            assert_eq!(aes.backend(), resolved(backend));
+            test_aes(&aes, &msg, &expected);
+        }
+    }
+
+    #[test]
+    fn aes_192() {
+        let key = decode_hex(&FIPS_KEY[..48]).unwrap();
+        let msg = decode_hex(FIPS_MSG).unwrap();
+        let expected = decode_hex("dda97ca4864cdfe06eaf70a0ec0d7191").unwrap();
+        for backend in BACKENDS {
+            let aes = Aes192Cipher::with_backend(&key, backend)
+                .expect("Key size error");
+            test_aes(&aes, &msg, &expected);
+        }
+    }
+
+    #[test]
+    fn aes_256() {
+        let key = decode_hex(FIPS_KEY).unwrap();
+        let msg = decode_hex(FIPS_MSG).unwrap();
+        let expected = decode_hex("8ea2b7ca516745bfeafc49904b496089").unwrap();
+        for backend in BACKENDS {
+            let aes = Aes256Cipher::with_backend(&key, backend)
+                .expect("Key size error");
+            test_aes(&aes, &msg, &expected);
+        }
+    }
+
+    // Chained blocks so every backend sees the same varied inputs
+    fn cross_check<C: Cipher<Block = [u8; 16]>>(
+            new: impl Fn(&[u8], AesBackend) -> C, key_size: usize) {
+        let key: Vec<u8> = (0..key_size).map(|x| (x * 17) as u8).collect();
+        let reference = new(&key, AesBackend::Reference);
+        let mut expected = [0x5a; 16];
+        let mut blocks = [[0; 16]; 64];
+        for block in blocks.iter_mut() {
+            reference.encrypt_block(&mut expected);
+            *block = expected;
+        }
+        let mut encrypted = blocks;
+        reference.encrypt_blocks(&mut encrypted);
+
+        for backend in BACKENDS {
+            let aes = new(&key, backend);
+            let mut block = [0x5a; 16];
+            for expected in &blocks {
+                aes.encrypt_block(&mut block);
+                assert_eq!(&block, expected);
+            }
+            for expected in blocks.iter().rev().skip(1) {
+                aes.decrypt_block(&mut block);
+                assert_eq!(&block, expected);
+            }
+
+            // Groups that do not fill the parallel blocks
+            let mut batch = blocks;
+            aes.encrypt_blocks(&mut batch[..13]);
+            aes.encrypt_blocks(&mut batch[13..]);
+            assert_eq!(batch, encrypted);
+            aes.decrypt_blocks(&mut batch);
+            assert_eq!(batch, blocks);
+        }
+    }
+
+    #[test]
+    fn aes_backends() {
+        cross_check(|k, b| Aes128Cipher::with_backend(k, b).unwrap(), 16);
+        cross_check(|k, b| Aes192Cipher::with_backend(k, b).unwrap(), 24);
+        cross_check(|k, b| Aes256Cipher::with_backend(k, b).unwrap(), 32);
+        let aes = Aes128Cipher::new(KEY).unwrap();
+        assert_eq!(aes.backend(), resolved(AesBackend::AesNi));
+    }
+
+    #[test]
+    fn aes_blocks() {
+        let aes = Aes128Cipher::new(KEY).expect("Key size error");
+        let mut block: [u8; 16] = MSG.try_into().unwrap();
+        aes.encrypt_block(&mut block);
+        assert_eq!(block.to_vec(), aes.encrypt(MSG).unwrap());
+
+        let mut blocks = [block; 3];
+        aes.decrypt_blocks(&mut blocks);
+        assert_eq!(blocks, [MSG; 3]);
+        aes.encrypt_blocks(&mut blocks);
+        assert_eq!(blocks, [block; 3]);
+        assert!(aes.encrypt(&MSG[..15]).is_err());
+    }
+
+    #[test]
+    fn aes_key_size() {
+        assert!(Aes128Cipher::new(&[0; 24]).is_err());
+        assert!(Aes192Cipher::new(&[0; 16]).is_err());
+        assert!(Aes256Cipher::new(&[0; 24]).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+// Bitsliced AES, constant time with respect to key and data. Up to BLOCKS
+// blocks are processed together: plane i holds bit i of every byte, the byte
+// at position p of block b being bit 16*b + p. Bytes within a block are in
+// column order (p = 4*column + row), like the reference implementation.
+
+pub(super) const BLOCKS: usize = 8;
+
+type State = [u128; 8];
+
+// Bits of the given rows in every column of every block
+const fn rows_mask(rows: u16) -> u128 {
+    cols_mask(rows, 0, 4)
+}
+
+// Bits of the given rows in columns from..to of every block
+const fn cols_mask(rows: u16, from: usize, to: usize) -> u128 {
+    let mut group: u16 = 0;
+    let mut col = from;
+    while col < to {
+        group |= rows << (4 * col);
+        col += 1;
+    }
+    group as u128 * 0x0001_0001_0001_0001_0001_0001_0001_0001
+}
+
+
+pub(super) struct RoundKeys {
+    keys: Vec<State>,
+}
+
+impl RoundKeys {
+    // Each round key is repeated for all the blocks
+    pub(super) fn new(keys: &[[u8; 16]]) -> Self {
+        let keys = keys.iter().map(|key| pack(&[*key; BLOCKS])).collect();
+        Self { keys }
+    }
+}
+
+
+pub(super) fn encrypt(blocks: &mut [[u8; 16]], keys: &RoundKeys) {
+    let rounds = keys.keys.len() - 1;
+    let mut state = pack(blocks);
+
+    xor(&mut state, &keys.keys[0]);
+
+    for key in &keys.keys[1..rounds] {
+        sub_bytes(&mut state);
+        shift_rows(&mut state);
+        mix_columns(&mut state);
+        xor(&mut state, key);
+    }
+    // Last round
+    sub_bytes(&mut state);
+    shift_rows(&mut state);
+    xor(&mut state, &keys.keys[rounds]);
+
+    unpack(&state, blocks);
+}
+
+pub(super) fn decrypt(blocks: &mut [[u8; 16]], keys: &RoundKeys) {
+    let rounds = keys.keys.len() - 1;
+    let mut state = pack(blocks);
+
+    xor(&mut state, &keys.keys[rounds]);
+
+    for key in keys.keys[1..rounds].iter().rev() {
+        inv_shift_rows(&mut state);
+        inv_sub_bytes(&mut state);
+        xor(&mut state, key);
+        inv_mix_columns(&mut state);
+    }
+
+    inv_shift_rows(&mut state);
+    inv_sub_bytes(&mut state);
+    xor(&mut state, &keys.keys[0]);
+
+    unpack(&state, blocks);
+}
+
+// Constant time S-box for the key schedule
+pub(super) fn sub_word(word: &mut [u8]) {
+    let mut block = [[0; 16]];
+    block[0][..word.len()].copy_from_slice(word);
+    let mut state = pack(&block);
+    sub_bytes(&mut state);
+    unpack(&state, &mut block);
+    word.copy_from_slice(&block[0][..word.len()]);
+}
+
+
+fn pack(blocks: &[[u8; 16]]) -> State {
+    let mut state = [0; 8];
+    for (b, block) in blocks.iter().enumerate() {
+        for (p, byte) in block.iter().enumerate() {
+            for (i, plane) in state.iter_mut().enumerate() {
+                *plane |= (((byte >> i) & 1) as u128) << (16*b + p);
+            }
+        }
+    }
+    state
+}
+
+fn unpack(state: &State, blocks: &mut [[u8; 16]]) {
+    for (b, block) in blocks.iter_mut().enumerate() {
+        for (p, byte) in block.iter_mut().enumerate() {
+            *byte = 0;
+            for (i, plane) in state.iter().enumerate() {
+                *byte |= (((plane >> (16*b + p)) & 1) as u8) << i;
+            }
+        }
+    }
+}
+
+fn xor(state: &mut State, other: &State) {
+    for (plane, x) in state.iter_mut().zip(other) {
+        *plane ^= x;
+    }
+}
+
+
+// Inverse in GF(2^8) as x^254 (0 maps to 0), then the affine transform
+fn sub_bytes(state: &mut State) {
+    *state = gf_inverse(state);
+    affine(state, &[0, 4, 5, 6, 7], 0x63);
+}
+
+fn inv_sub_bytes(state: &mut State) {
+    affine(state, &[2, 5, 7], 0x05);
+    *state = gf_inverse(state);
+}
+
+// Bit i becomes the sum of bits i + offset (mod 8) and bit i of the constant
+fn affine(state: &mut State, offsets: &[usize], constant: u8) {
+    let orig = *state;
+    for (i, plane) in state.iter_mut().enumerate() {
+        *plane = if (constant >> i) & 1 == 1 { !0 } else { 0 };
+        for offset in offsets {
+            *plane ^= orig[(i + offset) % 8];
+        }
+    }
+}
+
+fn gf_inverse(x: &State) -> State {
+    let x2 = gf_mult(x, x);
+    let x3 = gf_mult(&x2, x);
+    let x6 = gf_mult(&x3, &x3);
+    let x12 = gf_mult(&x6, &x6);
+    let x15 = gf_mult(&x12, &x3);
+    let mut x240 = x15;
+    for _ in 0..4 {
+        x240 = gf_mult(&x240, &x240);
+    }
+    let x252 = gf_mult(&x240, &x12);
+    gf_mult(&x252, &x2)
+}
+
+// Multiplication modulo x^8 + x^4 + x^3 + x + 1
+fn gf_mult(a: &State, b: &State) -> State {
+    let mut product = [0u128; 15];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] ^= a & b;
+        }
+    }
+    for k in (8..15).rev() {
+        for offset in [8, 7, 5, 4] {
+            product[k - offset] ^= product[k];
+        }
+    }
+    product[..8].try_into().unwrap()
+}
+
+// Multiplication by x
+fn xtime(state: &State) -> State {
+    let high = state[7];
+    [high, state[0] ^ high, state[1], state[2] ^ high, state[3] ^ high,
+     state[4], state[5], state[6]]
+}
+
+
+// Row r is rotated left by r columns
+fn shift_rows(state: &mut State) {
+    for plane in state.iter_mut() {
+        let mut shifted = 0;
+        for row in 0..4 {
+            let r = 1 << row;
+            shifted |= (*plane & cols_mask(r, row, 4)) >> (4 * row);
+            shifted |= (*plane & cols_mask(r, 0, row)) << (4 * (4 - row));
+        }
+        *plane = shifted;
+    }
+}
+
+fn inv_shift_rows(state: &mut State) {
+    for plane in state.iter_mut() {
+        let mut shifted = 0;
+        for row in 0..4 {
+            let r = 1 << row;
+            shifted |= (*plane & cols_mask(r, 0, 4 - row)) << (4 * row);
+            shifted |= (*plane & cols_mask(r, 4 - row, 4)) >> (4 * (4 - row));
+        }
+        *plane = shifted;
+    }
+}
+
+// Moves row r + n of each column to row r
+fn rotate_rows(state: &State, n: usize) -> State {
+    let low = rows_mask((1 << (4 - n)) - 1);
+    state.map(|plane| ((plane >> n) & low) | ((plane << (4 - n)) & !low))
+}
+
+// b_r = 2*a_r + 3*a_(r+1) + a_(r+2) + a_(r+3)
+fn mix_columns(state: &mut State) {
+    let r1 = rotate_rows(state, 1);
+    let r2 = rotate_rows(state, 2);
+    let r3 = rotate_rows(state, 3);
+    let mut sum = *state;
+    xor(&mut sum, &r1);
+    let mut mixed = xtime(&sum);
+    for rotated in [r1, r2, r3] {
+        xor(&mut mixed, &rotated);
+    }
+    *state = mixed;
+}
+
+// InvMixColumns is MixColumns after adding 4*(a_r + a_(r+2)) to each row
+fn inv_mix_columns(state: &mut State) {
+    let mut sum = rotate_rows(state, 2);
+    xor(&mut sum, state);
+    xor(state, &xtime(&xtime(&sum)));
+    mix_columns(state);
+}
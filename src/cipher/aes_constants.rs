@@ -1,13 +1,22 @@
-pub const ROUNDS: usize = 10;
 pub const BLOCK_SIZE: usize = 16;
 
+// Key sizes
+pub const AES128_KEY_SIZE: usize = 16;
+pub const AES192_KEY_SIZE: usize = 24;
+pub const AES256_KEY_SIZE: usize = 32;
+
+// Number of rounds
+pub const AES128_ROUNDS: usize = 10;
+pub const AES192_ROUNDS: usize = 12;
+pub const AES256_ROUNDS: usize = 14;
+
 // Key expansion round constant
 pub const R_CONST: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
 
 // Shift rows rotation
 pub enum ShiftRows {
-    LEFT,
-    RIGHT,
+    Left,
+    Right,
 }
 
 // Mix columns matrix
@@ -0,0 +1,101 @@
+use std::array;
+use std::arch::x86_64::{__m128i,_mm_aesdec_si128,_mm_aesdeclast_si128,
+                        _mm_aesenc_si128,_mm_aesenclast_si128,
+                        _mm_aesimc_si128,_mm_loadu_si128,_mm_setzero_si128,
+                        _mm_storeu_si128,_mm_xor_si128};
+
+
+// Blocks encrypted together so the instructions of independent blocks
+// overlap in the pipeline
+const PIPELINE: usize = 8;
+
+
+// Decryption keys are in reverse order with InvMixColumns applied
+// (equivalent inverse cipher)
+pub(super) struct RoundKeys {
+    enc: Vec<__m128i>,
+    dec: Vec<__m128i>,
+}
+
+impl RoundKeys {
+    // None when the CPU has no AES instructions, the other functions rely on
+    // the keys only existing after the check
+    pub(super) fn new(keys: &[[u8; 16]]) -> Option<Self> {
+        if !is_x86_feature_detected!("aes") {
+            return None;
+        }
+        Some(unsafe { Self::load(keys) })
+    }
+
+    #[target_feature(enable = "aes")]
+    unsafe fn load(keys: &[[u8; 16]]) -> Self {
+        let rounds = keys.len() - 1;
+        let mut enc = Vec::with_capacity(rounds + 1);
+        for key in keys {
+            enc.push(_mm_loadu_si128(key.as_ptr().cast()));
+        }
+
+        let mut dec = Vec::with_capacity(rounds + 1);
+        dec.push(enc[rounds]);
+        for &key in enc[1..rounds].iter().rev() {
+            dec.push(_mm_aesimc_si128(key));
+        }
+        dec.push(enc[0]);
+
+        Self { enc, dec }
+    }
+}
+
+
+pub(super) fn encrypt(blocks: &mut [[u8; 16]], keys: &RoundKeys) {
+    unsafe { process(blocks, &keys.enc, true) }
+}
+
+pub(super) fn decrypt(blocks: &mut [[u8; 16]], keys: &RoundKeys) {
+    unsafe { process(blocks, &keys.dec, false) }
+}
+
+
+#[target_feature(enable = "aes")]
+unsafe fn process(blocks: &mut [[u8; 16]], keys: &[__m128i],
+        encrypting: bool) {
+    let mut groups = blocks.chunks_exact_mut(PIPELINE);
+    for group in &mut groups {
+        process_group::<PIPELINE>(group.try_into().unwrap(), keys,
+                                  encrypting);
+    }
+    for block in groups.into_remainder() {
+        process_group(array::from_mut(block), keys, encrypting);
+    }
+}
+
+#[target_feature(enable = "aes")]
+unsafe fn process_group<const N: usize>(blocks: &mut [[u8; 16]; N],
+        keys: &[__m128i], encrypting: bool) {
+    let rounds = keys.len() - 1;
+    let mut state = [_mm_setzero_si128(); N];
+    for (s, block) in state.iter_mut().zip(blocks.iter()) {
+        *s = _mm_xor_si128(_mm_loadu_si128(block.as_ptr().cast()), keys[0]);
+    }
+
+    for &key in &keys[1..rounds] {
+        for s in state.iter_mut() {
+            *s = if encrypting {
+                _mm_aesenc_si128(*s, key)
+            } else {
+                _mm_aesdec_si128(*s, key)
+            };
+        }
+    }
+    for s in state.iter_mut() {
+        *s = if encrypting {
+            _mm_aesenclast_si128(*s, keys[rounds])
+        } else {
+            _mm_aesdeclast_si128(*s, keys[rounds])
+        };
+    }
+
+    for (block, s) in blocks.iter_mut().zip(state) {
+        _mm_storeu_si128(block.as_mut_ptr().cast(), s);
+    }
+}
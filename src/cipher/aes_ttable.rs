@@ -0,0 +1,160 @@
+use super::aes::galois_mult;
+use super::aes_constants as constants;
+
+
+// Each table entry is a column of MixColumns applied to a substituted byte,
+// the tables for the other rows are byte rotations of the first one
+static TE: [[u32; 256]; 4] = tables(&constants::S_BOX, [0x2, 0x1, 0x1, 0x3]);
+static TD: [[u32; 256]; 4] = tables(&constants::INV_S_BOX,
+                                    [0xE, 0x9, 0xD, 0xB]);
+
+
+// Round keys as big endian column words, decryption keys are in reverse
+// order with InvMixColumns applied (equivalent inverse cipher)
+pub(super) struct RoundKeys {
+    enc: Vec<u32>,
+    dec: Vec<u32>,
+}
+
+impl RoundKeys {
+    pub(super) fn new(keys: &[[u8; 16]]) -> Self {
+        let enc: Vec<u32> = keys.iter().flat_map(to_words).collect();
+
+        let rounds = keys.len() - 1;
+        let mut dec: Vec<u32> = Vec::with_capacity(enc.len());
+        for (round, key) in enc.chunks_exact(4).rev().enumerate() {
+            if round == 0 || round == rounds {
+                dec.extend_from_slice(key);
+            } else {
+                dec.extend(key.iter().map(|&w| inv_mix_column(w)));
+            }
+        }
+
+        Self { enc, dec }
+    }
+}
+
+
+pub(super) fn encrypt(plaintext: &[u8; 16], keys: &RoundKeys) -> [u8; 16] {
+    let (keys, last) = keys.enc.split_last_chunk::<4>().unwrap();
+    let mut s = to_words(plaintext);
+    xor_key(&mut s, &keys[..4]);
+
+    for key in keys[4..].chunks_exact(4) {
+        s = [
+            te(s[0], s[1], s[2], s[3]) ^ key[0],
+            te(s[1], s[2], s[3], s[0]) ^ key[1],
+            te(s[2], s[3], s[0], s[1]) ^ key[2],
+            te(s[3], s[0], s[1], s[2]) ^ key[3],
+        ];
+    }
+    // Last round has no MixColumns
+    let mut state = [
+        sub_word(&constants::S_BOX, s[0], s[1], s[2], s[3]),
+        sub_word(&constants::S_BOX, s[1], s[2], s[3], s[0]),
+        sub_word(&constants::S_BOX, s[2], s[3], s[0], s[1]),
+        sub_word(&constants::S_BOX, s[3], s[0], s[1], s[2]),
+    ];
+    xor_key(&mut state, last);
+
+    from_words(&state)
+}
+
+pub(super) fn decrypt(ciphertext: &[u8; 16], keys: &RoundKeys) -> [u8; 16] {
+    let (keys, last) = keys.dec.split_last_chunk::<4>().unwrap();
+    let mut s = to_words(ciphertext);
+    xor_key(&mut s, &keys[..4]);
+
+    // Rows are shifted right, so bytes come from the previous columns
+    for key in keys[4..].chunks_exact(4) {
+        s = [
+            td(s[0], s[3], s[2], s[1]) ^ key[0],
+            td(s[1], s[0], s[3], s[2]) ^ key[1],
+            td(s[2], s[1], s[0], s[3]) ^ key[2],
+            td(s[3], s[2], s[1], s[0]) ^ key[3],
+        ];
+    }
+    let mut state = [
+        sub_word(&constants::INV_S_BOX, s[0], s[3], s[2], s[1]),
+        sub_word(&constants::INV_S_BOX, s[1], s[0], s[3], s[2]),
+        sub_word(&constants::INV_S_BOX, s[2], s[1], s[0], s[3]),
+        sub_word(&constants::INV_S_BOX, s[3], s[2], s[1], s[0]),
+    ];
+    xor_key(&mut state, last);
+
+    from_words(&state)
+}
+
+
+// One output column from row 0 of a, row 1 of b, row 2 of c and row 3 of d
+fn te(a: u32, b: u32, c: u32, d: u32) -> u32 {
+    TE[0][(a >> 24) as usize]
+        ^ TE[1][(b >> 16) as u8 as usize]
+        ^ TE[2][(c >> 8) as u8 as usize]
+        ^ TE[3][d as u8 as usize]
+}
+
+fn td(a: u32, b: u32, c: u32, d: u32) -> u32 {
+    TD[0][(a >> 24) as usize]
+        ^ TD[1][(b >> 16) as u8 as usize]
+        ^ TD[2][(c >> 8) as u8 as usize]
+        ^ TD[3][d as u8 as usize]
+}
+
+fn sub_word(s_box: &[u8; 256], a: u32, b: u32, c: u32, d: u32) -> u32 {
+    u32::from_be_bytes([
+        s_box[(a >> 24) as usize],
+        s_box[(b >> 16) as u8 as usize],
+        s_box[(c >> 8) as u8 as usize],
+        s_box[d as u8 as usize],
+    ])
+}
+
+// TD includes the inverse S-box, substituting first cancels it out
+fn inv_mix_column(w: u32) -> u32 {
+    let s = |x: u32| constants::S_BOX[x as u8 as usize] as usize;
+    TD[0][s(w >> 24)] ^ TD[1][s(w >> 16)] ^ TD[2][s(w >> 8)] ^ TD[3][s(w)]
+}
+
+fn xor_key(state: &mut [u32; 4], key: &[u32]) {
+    for (s, k) in state.iter_mut().zip(key) {
+        *s ^= k;
+    }
+}
+
+fn to_words(block: &[u8; 16]) -> [u32; 4] {
+    let mut words = [0; 4];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    words
+}
+
+fn from_words(words: &[u32; 4]) -> [u8; 16] {
+    let mut block = [0; 16];
+    for (bytes, word) in block.chunks_exact_mut(4).zip(words) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    block
+}
+
+const fn tables(s_box: &[u8; 256], column: [u8; 4]) -> [[u32; 256]; 4] {
+    let mut tables = [[0; 256]; 4];
+    let mut x = 0;
+    while x < 256 {
+        let s = s_box[x];
+        let word = u32::from_be_bytes([
+            galois_mult(column[0], s),
+            galois_mult(column[1], s),
+            galois_mult(column[2], s),
+            galois_mult(column[3], s),
+        ]);
+        let mut row = 0;
+        while row < 4 {
+            tables[row][x] = word.rotate_right(8 * row as u32);
+            row += 1;
+        }
+        x += 1;
+    }
+    tables
+}
@@ -0,0 +1,254 @@
+use crate::error::{InvalidDataLen,InvalidKeyLen,InvalidIvLen,ModeError};
+use crate::utils;
+
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+pub const XNONCE_SIZE: usize = 24;
+pub const BLOCK_SIZE: usize = 64;
+
+// "expand 32-byte k"
+const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+
+// RFC 8439, 96 bit nonce and 32 bit block counter
+pub struct ChaCha20 {
+    key: [u32; 8],
+}
+
+impl ChaCha20 {
+    pub fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        if key.len() != KEY_SIZE {
+            return Err(InvalidKeyLen);
+        }
+        Ok(Self { key: words_le(key).try_into().unwrap() })
+    }
+
+    // Counter is the block counter of the first keystream block, data that
+    // would need the counter to wrap around is rejected
+    pub fn encrypt(&self, data: &[u8], nonce: &[u8], counter: u32)
+            -> Result<Vec<u8>, ModeError> {
+        if nonce.len() != NONCE_SIZE {
+            return Err(InvalidIvLen.into());
+        }
+        let blocks = data.len().div_ceil(BLOCK_SIZE) as u64;
+        if blocks > (1 << 32) - counter as u64 {
+            return Err(InvalidDataLen.into());
+        }
+        let nonce: [u32; 3] = words_le(nonce).try_into().unwrap();
+        let mut encrypted = Vec::<u8>::with_capacity(data.len());
+
+        let counters = counter..=u32::MAX;
+        for (counter, block) in counters.zip(data.chunks(BLOCK_SIZE)) {
+            let mut stream = self.block(counter, &nonce);
+            utils::xor_slice(&mut stream, block);
+            encrypted.extend_from_slice(&stream[..block.len()]);
+        }
+
+        Ok(encrypted)
+    }
+
+    pub fn decrypt(&self, data: &[u8], nonce: &[u8], counter: u32)
+            -> Result<Vec<u8>, ModeError> {
+        self.encrypt(data, nonce, counter)
+    }
+
+    fn block(&self, counter: u32, nonce: &[u32; 3]) -> [u8; BLOCK_SIZE] {
+        let mut state = [0u32; 16];
+        state[..4].copy_from_slice(&SIGMA);
+        state[4..12].copy_from_slice(&self.key);
+        state[12] = counter;
+        state[13..].copy_from_slice(nonce);
+
+        let mut working_state = state;
+        rounds(&mut working_state);
+
+        let mut output = [0u8; BLOCK_SIZE];
+        for i in 0..16 {
+            let word = working_state[i].wrapping_add(state[i]);
+            output[4*i..4*i+4].copy_from_slice(&word.to_le_bytes());
+        }
+        output
+    }
+}
+
+
+// draft-irtf-cfrg-xchacha, 192 bit nonce through an HChaCha20 derived subkey
+pub struct XChaCha20 {
+    key: [u32; 8],
+}
+
+impl XChaCha20 {
+    pub fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        if key.len() != KEY_SIZE {
+            return Err(InvalidKeyLen);
+        }
+        Ok(Self { key: words_le(key).try_into().unwrap() })
+    }
+
+    pub fn encrypt(&self, data: &[u8], nonce: &[u8], counter: u32)
+            -> Result<Vec<u8>, ModeError> {
+        let (chacha, chacha_nonce) = self.derive(nonce)?;
+        chacha.encrypt(data, &chacha_nonce, counter)
+    }
+
+    pub fn decrypt(&self, data: &[u8], nonce: &[u8], counter: u32)
+            -> Result<Vec<u8>, ModeError> {
+        self.encrypt(data, nonce, counter)
+    }
+
+    // Returns the ChaCha20 instance and 96 bit nonce equivalent to the
+    // extended nonce
+    pub fn derive(&self, nonce: &[u8])
+            -> Result<(ChaCha20, [u8; NONCE_SIZE]), InvalidIvLen> {
+        if nonce.len() != XNONCE_SIZE {
+            return Err(InvalidIvLen);
+        }
+        let subkey = hchacha20(&self.key, nonce[..16].try_into().unwrap());
+        let mut chacha_nonce = [0u8; NONCE_SIZE];
+        chacha_nonce[4..].copy_from_slice(&nonce[16..]);
+        Ok((ChaCha20 { key: subkey }, chacha_nonce))
+    }
+}
+
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]); s[d] ^= s[a]; s[d] = s[d].rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]); s[b] ^= s[c]; s[b] = s[b].rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]); s[d] ^= s[a]; s[d] = s[d].rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]); s[b] ^= s[c]; s[b] = s[b].rotate_left(7);
+}
+
+// 20 rounds, alternating column and diagonal rounds
+fn rounds(state: &mut [u32; 16]) {
+    for _ in 0..10 {
+        quarter_round(state, 0, 4,  8, 12);
+        quarter_round(state, 1, 5,  9, 13);
+        quarter_round(state, 2, 6, 10, 14);
+        quarter_round(state, 3, 7, 11, 15);
+        quarter_round(state, 0, 5, 10, 15);
+        quarter_round(state, 1, 6, 11, 12);
+        quarter_round(state, 2, 7,  8, 13);
+        quarter_round(state, 3, 4,  9, 14);
+    }
+}
+
+fn hchacha20(key: &[u32; 8], nonce: &[u8; 16]) -> [u32; 8] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&SIGMA);
+    state[4..12].copy_from_slice(key);
+    state[12..].copy_from_slice(&words_le(nonce));
+
+    rounds(&mut state);
+
+    let mut subkey = [0u32; 8];
+    subkey[..4].copy_from_slice(&state[..4]);
+    subkey[4..].copy_from_slice(&state[12..]);
+    subkey
+}
+
+fn words_le(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks_exact(4)
+        .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{ChaCha20,XChaCha20,hchacha20,words_le};
+    use crate::error::ModeError;
+    use crate::utils::decode_hex;
+
+    const MSG: &[u8] = "Ladies and Gentlemen of the class of '99: If I could \
+                        offer you only one tip for the future, sunscreen \
+                        would be it.".as_bytes();
+
+    fn key() -> Vec<u8> {
+        (0x00..0x20).collect()
+    }
+
+    #[test]
+    fn chacha20() {
+        // RFC 8439 2.4.2
+        let nonce = decode_hex("000000000000004a00000000").unwrap();
+        let expected = decode_hex("6e2e359a2568f98041ba0728dd0d6981e97e7aec1d\
+                                   4360c20a27afccfd9fae0bf91b65c5524733ab8f59\
+                                   3dabcd62b3571639d624e65152ab8f530c359f0861\
+                                   d807ca0dbf500d6a6156a38e088a22b65e52bc514d\
+                                   16ccf806818ce91ab77937365af90bbf74a35be6b4\
+                                   0b8eedf2785e42874d").unwrap();
+        let chacha = ChaCha20::new(&key()).expect("Key size error");
+        let encrypted = chacha.encrypt(MSG, &nonce, 1).unwrap();
+        assert_eq!(encrypted, expected);
+        let decrypted = chacha.decrypt(&encrypted, &nonce, 1).unwrap();
+        assert_eq!(decrypted, MSG);
+        assert!(chacha.encrypt(MSG, &nonce[..8], 1).is_err());
+    }
+
+    #[test]
+    fn chacha20_counter_overflow() {
+        let nonce = [0; 12];
+        let chacha = ChaCha20::new(&key()).expect("Key size error");
+        assert!(chacha.encrypt(&[0; 64], &nonce, u32::MAX).is_ok());
+        let result = chacha.encrypt(&[0; 65], &nonce, u32::MAX);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+        let result = chacha.encrypt(&[0; 129], &nonce, u32::MAX - 1);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+    }
+
+    #[test]
+    fn hchacha20_subkey() {
+        // draft-irtf-cfrg-xchacha 2.2.1
+        let key: [u32; 8] = words_le(&key()).try_into().unwrap();
+        let nonce = decode_hex("000000090000004a0000000031415927").unwrap();
+        let expected = decode_hex("82413b4227b27bfed30e42508a877d73a0f9e4d58a\
+                                   74a853c12ec41326d3ecdc").unwrap();
+        let subkey = hchacha20(&key, nonce.as_slice().try_into().unwrap());
+        assert_eq!(subkey.to_vec(), words_le(&expected));
+    }
+
+    #[test]
+    fn xchacha20() {
+        // draft-irtf-cfrg-xchacha A.3.2
+        let key: Vec<u8> = (0x80..0xa0).collect();
+        let nonce = decode_hex("404142434445464748494a4b4c4d4e4f50515253545556\
+                                58").unwrap();
+        let msg = "The dhole (pronounced \"dole\") is also known as the \
+                   Asiatic wild dog, red dog, and whistling dog. It is about \
+                   the size of a German shepherd but looks more like a \
+                   long-legged fox. This highly elusive and skilled jumper is \
+                   classified with wolves, coyotes, jackals, and foxes in the \
+                   taxonomic family Canidae.".as_bytes();
+        let expected = decode_hex("7d0a2e6b7f7c65a236542630294e063b7ab9b555a5\
+                                   d5149aa21e4ae1e4fbce87ecc8e08a8b5e350abe62\
+                                   2b2ffa617b202cfad72032a3037e76ffdcdc4376ee\
+                                   053a190d7e46ca1de04144850381b9cb29f0519153\
+                                   86b8a710b8ac4d027b8b050f7cba5854e028d564e4\
+                                   53b8a968824173fc16488b8970cac828f11ae53cab\
+                                   d20112f87107df24ee6183d2274fe4c8b1485534ef\
+                                   2c5fbc1ec24bfc3663efaa08bc047d29d25043532d\
+                                   b8391a8a3d776bf4372a6955827ccb0cdd4af403a7\
+                                   ce4c63d595c75a43e045f0cce1f29c8b93bd65afc5\
+                                   974922f214a40b7c402cdb91ae73c0b63615cdad04\
+                                   80680f16515a7ace9d39236464328a37743ffc28f4\
+                                   ddb324f4d0f5bbdc270c65b1749a6efff1fbaa0953\
+                                   6175ccd29fb9e6057b307320d316838a9c71f70b5b\
+                                   5907a66f7ea49aadc409").unwrap();
+        let xchacha = XChaCha20::new(&key).expect("Key size error");
+        let encrypted = xchacha.encrypt(msg, &nonce, 1).unwrap();
+        assert_eq!(encrypted, expected);
+        assert_eq!(xchacha.decrypt(&encrypted, &nonce, 1).unwrap(), msg);
+    }
+
+    #[test]
+    fn xchacha20_derive() {
+        let nonce: Vec<u8> = (0x40..0x58).collect();
+        let xchacha = XChaCha20::new(&key()).expect("Key size error");
+        let encrypted = xchacha.encrypt(MSG, &nonce, 0).unwrap();
+        let (chacha, chacha_nonce) = xchacha.derive(&nonce).unwrap();
+        assert_eq!(chacha.encrypt(MSG, &chacha_nonce, 0).unwrap(), encrypted);
+        assert_eq!(xchacha.decrypt(&encrypted, &nonce, 0).unwrap(), MSG);
+        assert!(xchacha.encrypt(MSG, &nonce[..12], 0).is_err());
+    }
+}
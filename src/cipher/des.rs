@@ -1,51 +1,154 @@
-use crate::error::{InvalidKeyLen,InvalidDataLen};
+use crate::error::InvalidKeyLen;
 use super::des_constants as constants;
 use super::Cipher;
 
 
 pub struct DesCipher {
-    keys: Box<[[u8; 6]; 16]>, // Internal keys are 48 bits long
+    keys: Schedule,
 }
 
 impl Cipher for DesCipher {
-    const BLOCK_SIZE: usize = constants::BLOCK_SIZE;
+    const KEY_SIZE: usize = constants::KEY_SIZE;
+
+    type Block = [u8; constants::BLOCK_SIZE];
 
     fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
-        let key: [u8; Self::BLOCK_SIZE] = match key.try_into() {
+        let key: [u8; Self::KEY_SIZE] = match key.try_into() {
             Ok(key) => key,
             Err(_) => return Err(InvalidKeyLen),
         };
 
-        let keys = key_expansion(&key);
+        let keys = Schedule::new(&key);
         Ok(Self { keys })
     }
 
-    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, InvalidDataLen> {
-        match data.try_into() {
-            Ok(data) => {
-                let ciphertext = des_algorithm(&data, &self.keys);
-                Ok(Vec::from(ciphertext))
-            },
-            Err(_) => Err(InvalidDataLen)
+    fn encrypt_block(&self, block: &mut Self::Block) {
+        *block = des(block, &self.keys.enc);
+    }
+
+    fn decrypt_block(&self, block: &mut Self::Block) {
+        *block = des(block, &self.keys.dec);
+    }
+}
+
+
+// Round keys split into the 6 bit pieces that go into each S-box
+pub type RoundKeys = [[u8; 8]; 16];
+
+// Decryption uses the round keys in reverse order
+pub struct Schedule {
+    pub enc: RoundKeys,
+    pub dec: RoundKeys,
+}
+
+impl Schedule {
+    pub fn new(key: &[u8; 8]) -> Self {
+        let mut enc = [[0; 8]; 16];
+        for (round, key) in enc.iter_mut().zip(key_expansion(key).iter()) {
+            let mut key_64 = [0; 8];
+            key_64[2..].copy_from_slice(key);
+            let key_64 = u64::from_be_bytes(key_64);
+            for (i, piece) in round.iter_mut().enumerate() {
+                *piece = ((key_64 >> (42 - 6*i)) & 0x3F) as u8;
+            }
         }
+
+        let mut dec = enc;
+        dec.reverse();
+        Self { enc, dec }
     }
+}
 
-    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, InvalidDataLen> {
-        match data.try_into() {
-            Ok(data) => {
-                let mut keys = self.keys.clone();
-                keys.reverse();
-                let plaintext = des_algorithm(&data, &keys);
-                Ok(Vec::from(plaintext))
-            },
-                Err(_) => Err(InvalidDataLen)
+
+// Each S-box output already moved to its place by the P permutation
+static SP_BOXES: [[u32; 64]; 8] = sp_boxes();
+
+const fn sp_boxes() -> [[u32; 64]; 8] {
+    let mut sp_boxes = [[0; 64]; 8];
+    let mut i = 0;
+    while i < 8 {
+        let mut piece = 0;
+        while piece < 64 {
+            let outer_bits = (piece & 0x20) >> 4 | (piece & 0x01);
+            let inner_bits = (piece >> 1) & 0x0F;
+            let s = constants::S_BOXES[i][outer_bits][inner_bits] as u32;
+            let word = s << (28 - 4*i);
+
+            let mut permuted = 0;
+            let mut j = 0;
+            while j < 32 {
+                let bit = (word >> (32 - constants::P[j] as u32)) & 0x1;
+                permuted |= bit << (31 - j);
+                j += 1;
+            }
+            sp_boxes[i][piece] = permuted;
+            piece += 1;
         }
+        i += 1;
+    }
+    sp_boxes
+}
+
+// Swaps the bits of a selected by mask with the bits of b n positions lower
+fn delta_swap(a: &mut u32, b: &mut u32, n: u32, mask: u32) {
+    let t = ((*a >> n) ^ *b) & mask;
+    *b ^= t;
+    *a ^= t << n;
+}
+
+// Returns the left and right halves
+pub fn initial_permutation(block: &[u8; 8]) -> (u32, u32) {
+    let mut left = u32::from_be_bytes(block[..4].try_into().unwrap());
+    let mut right = u32::from_be_bytes(block[4..].try_into().unwrap());
+    delta_swap(&mut left, &mut right, 4, 0x0F0F_0F0F);
+    delta_swap(&mut left, &mut right, 16, 0x0000_FFFF);
+    delta_swap(&mut right, &mut left, 2, 0x3333_3333);
+    delta_swap(&mut right, &mut left, 8, 0x00FF_00FF);
+    delta_swap(&mut left, &mut right, 1, 0x5555_5555);
+    (left, right)
+}
+
+pub fn final_permutation(mut left: u32, mut right: u32) -> [u8; 8] {
+    delta_swap(&mut left, &mut right, 1, 0x5555_5555);
+    delta_swap(&mut right, &mut left, 8, 0x00FF_00FF);
+    delta_swap(&mut right, &mut left, 2, 0x3333_3333);
+    delta_swap(&mut left, &mut right, 16, 0x0000_FFFF);
+    delta_swap(&mut left, &mut right, 4, 0x0F0F_0F0F);
+    ((left as u64) << 32 | right as u64).to_be_bytes()
+}
+
+// The expansion takes bits 4i to 4i+5 of the word for the i-th S-box (bit
+// 0 being bit 32), so rotating right by one lines them up
+fn feistel(word: u32, key: &[u8; 8]) -> u32 {
+    let word = word.rotate_right(1);
+    let mut result = 0;
+    for (i, piece) in key.iter().enumerate() {
+        let bits = (word.rotate_left(4 * i as u32) >> 26) as u8;
+        result ^= SP_BOXES[i][(bits ^ piece) as usize];
     }
+    result
+}
+
+// 16 rounds on the permuted halves, the halves are returned swapped as the
+// last round does not swap them
+pub fn rounds(left: u32, right: u32, keys: &RoundKeys) -> (u32, u32) {
+    let (mut left, mut right) = (left, right);
+    for pair in keys.chunks_exact(2) {
+        left ^= feistel(right, &pair[0]);
+        right ^= feistel(left, &pair[1]);
+    }
+    (right, left)
+}
+
+fn des(block: &[u8; 8], keys: &RoundKeys) -> [u8; 8] {
+    let (left, right) = initial_permutation(block);
+    let (left, right) = rounds(left, right, keys);
+    final_permutation(left, right)
 }
 
 
-fn key_expansion(initial_key: &[u8; 8]) -> Box<[[u8; 6]; 16]> {
-    let mut key: u64 = u64::from_be_bytes(initial_key.clone());
+pub fn key_expansion(initial_key: &[u8; 8]) -> Box<[[u8; 6]; 16]> {
+    let mut key: u64 = u64::from_be_bytes(*initial_key);
     let mut keys: Box<[[u8; 6]; 16]> = Box::default();
 
     permutation(&mut key, constants::PC_1.as_slice(), 64);
@@ -73,16 +176,17 @@ fn permutation(chunk: &mut u64, p: &[u8], chunk_size: u8) {
     *chunk = 0;
 
     for i in 0..p.len() {
-       *chunk |= ((orig_chunk >> chunk_size-p[i]) & 0x1) << (p.len()-i-1);
+       *chunk |= ((orig_chunk >> (chunk_size-p[i])) & 0x1) << (p.len()-i-1);
     }
 }
 
+// Bit by bit implementation following the standard, kept to check the table
+// driven one against
+#[cfg(test)]
 fn feistel_function(word: u32, key: &[u8; 6]) -> u32 {
     let mut word: u64 = word.into();
     permutation(&mut word, constants::E.as_slice(), 32);
 
-
-
     let mut key_64: [u8; 8] = [0; 8];
     key_64[2..].copy_from_slice(key);
     let xor = word ^ u64::from_be_bytes(key_64);
@@ -100,25 +204,28 @@ fn feistel_function(word: u32, key: &[u8; 6]) -> u32 {
     result as u32
 }
 
+#[cfg(test)]
 fn feistel_round(chunk: &mut u64, key: &[u8; 6]) {
     let mut left: u32 = (*chunk >> 32) as u32;
     let right: u32 = (*chunk & 0xFFFFFFFF) as u32;
-    left = left ^ feistel_function(right, key);
+    left ^= feistel_function(right, key);
     *chunk = (right as u64) << 32 | left as u64;
 }
 
-pub fn des_algorithm(plaintext: &[u8; 8], keys: &[[u8; 6]; 16]) -> [u8; 8] {
-    let mut chunk: u64 = u64::from_be_bytes(plaintext.clone());
+#[cfg(test)]
+pub fn des_algorithm<'a>(plaintext: &[u8; 8],
+        keys: impl Iterator<Item = &'a [u8; 6]>) -> [u8; 8] {
+    let mut chunk: u64 = u64::from_be_bytes(*plaintext);
 
     // Initial permutation
     permutation(&mut chunk, constants::IP.as_slice(), 64);
 
     // 15 feistel rounds
-    for i in 0..16 {
-        feistel_round(&mut chunk, &keys[i]);
+    for key in keys {
+        feistel_round(&mut chunk, key);
     }
     // Last round does swaps again
-    chunk = (chunk << 32) | (chunk >> 32);
+    chunk = chunk.rotate_left(32);
 
     // Final permutation
     permutation(&mut chunk, constants::FP.as_slice(), 64);
@@ -129,7 +236,9 @@ pub fn des_algorithm(plaintext: &[u8; 8], keys: &[[u8; 6]; 16]) -> [u8; 8] {
 
 #[cfg(test)]
 mod tests {
-    use super::{DesCipher,Cipher};
+    use std::time::Instant;
+
+    use super::{des_algorithm,key_expansion,DesCipher,Cipher};
     use crate::utils::decode_hex;
 
     const KEY: &[u8] = "computer".as_bytes();
@@ -144,4 +253,50 @@ mod tests {
         let decrypted = des.decrypt(&encrypted).unwrap();
         assert_eq!(decrypted, MSG);
     }
+
+    #[test]
+    fn des_reference() {
+        // Each output is used as the next key and plaintext
+        let mut key = [0x13, 0x34, 0x57, 0x79, 0x9b, 0xbc, 0xdf, 0xf1];
+        let mut block = *b"01234567";
+        for _ in 0..64 {
+            let des = DesCipher::new(&key).unwrap();
+            let keys = key_expansion(&key);
+            let expected = des_algorithm(&block, keys.iter());
+            let mut encrypted = block;
+            des.encrypt_block(&mut encrypted);
+            assert_eq!(encrypted, expected);
+            assert_eq!(des_algorithm(&encrypted, keys.iter().rev()), block);
+            des.decrypt_block(&mut encrypted);
+            assert_eq!(encrypted, block);
+            key = expected;
+            block = expected;
+        }
+    }
+
+    #[test]
+    #[ignore] // cargo test --release des_benchmark -- --ignored --nocapture
+    fn des_benchmark() {
+        const BLOCKS: usize = 1 << 18;
+        let des = DesCipher::new(KEY).unwrap();
+        let keys = key_expansion(KEY.try_into().unwrap());
+
+        let start = Instant::now();
+        let mut expected = [0; 8];
+        for _ in 0..BLOCKS {
+            expected = des_algorithm(&expected, keys.iter());
+        }
+        let reference = start.elapsed();
+
+        let start = Instant::now();
+        let mut block = [0; 8];
+        for _ in 0..BLOCKS {
+            des.encrypt_block(&mut block);
+        }
+        let fast = start.elapsed();
+
+        assert_eq!(block, expected);
+        println!("{BLOCKS} blocks: des_algorithm {reference:?}, \
+                  DesCipher {fast:?}");
+    }
 }
@@ -1,7 +1,14 @@
-// Block size
+// Block and key size
 pub const BLOCK_SIZE: usize = 8;
+pub const KEY_SIZE: usize = 8;
 
-// Initial permutation
+// Triple DES key sizes
+pub const TDES_EDE2_KEY_SIZE: usize = 16;
+pub const TDES_EDE3_KEY_SIZE: usize = 24;
+
+// Initial permutation, the cipher uses the equivalent bit swaps and the
+// tables are kept for the reference implementation
+#[cfg(test)]
 pub const IP: [u8; 64] = [
     58, 50, 42, 34, 26, 18, 10,  2, 60, 52, 44, 36, 28, 20, 12,  4,
     62, 54, 46, 38, 30, 22, 14,  6, 64, 56, 48, 40, 32, 24, 16,  8,
@@ -10,6 +17,7 @@ pub const IP: [u8; 64] = [
 ];
 
 // Final permutation (IP^-1)
+#[cfg(test)]
 pub const FP: [u8; 64] = [
     40,  8, 48, 16, 56, 24, 64, 32, 39,  7, 47, 15, 55, 23, 63, 31,
     38,  6, 46, 14, 54, 22, 62, 30, 37,  5, 45, 13, 53, 21, 61, 29,
@@ -26,7 +34,8 @@ pub const P: [u8; 32] = [
     19, 13, 30,  6, 22, 11,  4, 25,
 ];
 
-// Expansion function (32 -> 48 bits)
+// Expansion function (32 -> 48 bits), done by rotations in the cipher
+#[cfg(test)]
 pub const E: [u8; 48] = [
     32,  1,  2,  3,  4,  5,  4,  5,  6,  7,  8,  9,
      8,  9, 10, 11, 12, 13, 12, 13, 14, 15, 16, 17,
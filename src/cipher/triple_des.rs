@@ -0,0 +1,119 @@
+use crate::error::InvalidKeyLen;
+use super::des::{final_permutation,initial_permutation,rounds,Schedule};
+use super::des_constants as constants;
+use super::Cipher;
+
+
+pub struct TripleDesCipher {
+    keys: [Schedule; 3],
+}
+
+impl Cipher for TripleDesCipher {
+    const KEY_SIZE: usize = constants::TDES_EDE3_KEY_SIZE;
+
+    type Block = [u8; constants::BLOCK_SIZE];
+
+    // Two-key (K1, K2, K1) or three-key (K1, K2, K3) variants
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        let k3_start = match key.len() {
+            constants::TDES_EDE2_KEY_SIZE => 0,
+            constants::TDES_EDE3_KEY_SIZE => 2*Self::BLOCK_SIZE,
+            _ => return Err(InvalidKeyLen),
+        };
+
+        let k1 = &key[..Self::BLOCK_SIZE];
+        let k2 = &key[Self::BLOCK_SIZE..2*Self::BLOCK_SIZE];
+        let k3 = &key[k3_start..k3_start+Self::BLOCK_SIZE];
+        let keys = [
+            Schedule::new(k1.try_into().unwrap()),
+            Schedule::new(k2.try_into().unwrap()),
+            Schedule::new(k3.try_into().unwrap()),
+        ];
+        Ok(Self { keys })
+    }
+
+    // The final and initial permutations between the steps cancel out
+    fn encrypt_block(&self, block: &mut Self::Block) {
+        let (left, right) = initial_permutation(block);
+        let (left, right) = rounds(left, right, &self.keys[0].enc);
+        let (left, right) = rounds(left, right, &self.keys[1].dec);
+        let (left, right) = rounds(left, right, &self.keys[2].enc);
+        *block = final_permutation(left, right);
+    }
+
+    fn decrypt_block(&self, block: &mut Self::Block) {
+        let (left, right) = initial_permutation(block);
+        let (left, right) = rounds(left, right, &self.keys[2].dec);
+        let (left, right) = rounds(left, right, &self.keys[1].enc);
+        let (left, right) = rounds(left, right, &self.keys[0].dec);
+        *block = final_permutation(left, right);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{TripleDesCipher,Cipher};
+    use crate::cipher::DesCipher;
+    use crate::modes::{cbc,ctr};
+    use crate::utils::decode_hex;
+
+    // NIST SP 800-67 example
+    const KEY: &str = "0123456789abcdef23456789abcdef01456789abcdef0123";
+    const MSG: &[u8] = "The qufck brown fox jump".as_bytes();
+
+    #[test]
+    fn triple_des_ede3() {
+        let key = decode_hex(KEY).unwrap();
+        let expected = decode_hex("a826fd8ce53b855fcce21c8112256fe6\
+                                   68d5c05dd9b6b900").unwrap();
+        let tdes = TripleDesCipher::new(&key).expect("Key size error");
+        for i in (0..MSG.len()).step_by(8) {
+            let encrypted = tdes.encrypt(&MSG[i..i+8]).unwrap();
+            assert_eq!(encrypted, expected[i..i+8]);
+            let decrypted = tdes.decrypt(&encrypted).unwrap();
+            assert_eq!(decrypted, MSG[i..i+8]);
+        }
+    }
+
+    #[test]
+    fn triple_des_ede2() {
+        let key = decode_hex(&KEY[..32]).unwrap();
+        let expected = decode_hex("c44862f70cf2fbdc").unwrap();
+        let tdes = TripleDesCipher::new(&key).expect("Key size error");
+        let encrypted = tdes.encrypt(&MSG[..8]).unwrap();
+        assert_eq!(encrypted, expected);
+        let decrypted = tdes.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted, MSG[..8]);
+    }
+
+    #[test]
+    fn triple_des_single_key() {
+        // CAVP TECBvartext, K1 = K2 = K3 degrades to single DES
+        let key = decode_hex("010101010101010101010101010101010101010101010101")
+            .unwrap();
+        let msg = decode_hex("8000000000000000").unwrap();
+        let expected = decode_hex("95f8a5e5dd31d900").unwrap();
+        let tdes = TripleDesCipher::new(&key).expect("Key size error");
+        let des = DesCipher::new(&key[..8]).expect("Key size error");
+        assert_eq!(tdes.encrypt(&msg).unwrap(), expected);
+        assert_eq!(des.encrypt(&msg).unwrap(), expected);
+    }
+
+    #[test]
+    fn triple_des_modes() {
+        let key = decode_hex(KEY).unwrap();
+        let tdes = TripleDesCipher::new(&key).expect("Key size error");
+        let iv = [0; 8];
+        let encrypted = cbc::encrypt(MSG, &tdes, &iv).unwrap();
+        assert_eq!(cbc::decrypt(&encrypted, &tdes, &iv).unwrap(), MSG);
+        let encrypted = ctr::encrypt(MSG, &tdes, &iv[..4]).unwrap();
+        assert_eq!(ctr::decrypt(&encrypted, &tdes, &iv[..4]).unwrap(), MSG);
+    }
+
+    #[test]
+    fn triple_des_key_size() {
+        assert!(TripleDesCipher::new(&[0; 8]).is_err());
+        assert!(TripleDesCipher::new(&[0; 32]).is_err());
+    }
+}
@@ -14,8 +14,52 @@ macro_rules! error_decl {
     }
 }
 
+// Groups several of the errors above for operations that can fail in more
+// than one way
+macro_rules! error_enum_decl {
+    ( $name:ident, $( $variant:ident ),+ ) =>
+    {
+        #[derive(Debug)]
+        pub enum $name {
+            $( $variant($variant), )+
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                match self {
+                    $( $name::$variant(e) => e.fmt(f), )+
+                }
+            }
+        }
+
+        impl std::error::Error for $name {}
+
+        $(
+            impl From<$variant> for $name {
+                fn from(e: $variant) -> Self {
+                    $name::$variant(e)
+                }
+            }
+        )+
+    }
+}
+
 
 error_decl!(InvalidKeyLen, "Invalid key length");
+error_decl!(WeakKey, "Weak key");
 error_decl!(InvalidDataLen, "Invalid data length");
 error_decl!(InvalidIvLen, "Invalid iv/nonce length");
+error_decl!(InvalidOutputLen, "Invalid output length");
+error_decl!(InvalidPadding, "Invalid padding");
+error_decl!(InvalidTagLen, "Invalid authentication tag length");
 error_decl!(IncorrectMac, "MAC signature was incorrect");
+error_decl!(AuthenticationFailed, "Authentication tag verification failed");
+error_decl!(IntegrityCheckFailed, "Key wrap integrity check failed");
+error_decl!(InvalidIterationCount, "Iteration count must be positive");
+
+error_enum_decl!(KeyError, InvalidKeyLen, WeakKey);
+error_enum_decl!(ModeError, InvalidIvLen, InvalidDataLen, InvalidPadding);
+error_enum_decl!(KeyWrapError, InvalidDataLen, IntegrityCheckFailed);
+error_enum_decl!(AeadError, InvalidIvLen, InvalidTagLen, InvalidDataLen,
+                 AuthenticationFailed);
+error_enum_decl!(KdfError, InvalidIterationCount, InvalidOutputLen);
@@ -1,11 +1,24 @@
+mod md_common;
+mod md5;
+mod md5_constants;
+mod sha1;
+mod sha1_constants;
 mod sha2;
 mod sha2_common;
 mod sha2_constants;
+mod sha3;
+mod sha3_common;
+mod sha3_constants;
 
+pub use md5::Md5;
+pub use sha1::Sha1;
 pub use sha2::{Sha224,Sha256,Sha384,Sha512,Sha512_224,Sha512_256};
+pub use sha3::{Sha3_224,Sha3_256,Sha3_384,Sha3_512,Keccak256,Shake128,
+               Shake256};
 
 
-pub trait Hash {
+// Clone is used to snapshot partially hashed states, e.g. keyed HMAC states
+pub trait Hash: Clone {
     const DIGEST_SIZE: usize;
     const BLOCK_SIZE: usize;
 
@@ -21,3 +34,19 @@ pub trait Hash {
         Self::DIGEST_SIZE
     }
 }
+
+// Extendable-output function, squeeze can be called repeatedly to read more
+// output. Once squeezing has started the input is closed: update panics
+// until reset is called
+pub trait Xof {
+    const BLOCK_SIZE: usize;
+
+    fn new() -> Self;
+
+    // Panics if called after squeeze without an intermediate reset
+    fn update(&mut self, data: &[u8]);
+
+    fn squeeze(&mut self, len: usize) -> Vec<u8>;
+
+    fn reset(&mut self);
+}
@@ -0,0 +1,125 @@
+use super::md5_constants as constants;
+use super::md_common::MdCommon;
+
+
+#[derive(Clone)]
+pub struct Md5 {
+    hash: [u32; 4],
+    data: [u8; constants::MD5_BLOCK_SIZE],
+    pending_data: usize,
+    total_data: usize,
+}
+
+impl MdCommon for Md5 {
+    const DIGEST_SIZE: usize = constants::MD5_DIGEST_SIZE;
+    const BLOCK_SIZE: usize = constants::MD5_BLOCK_SIZE;
+    const BIG_ENDIAN: bool = false;
+
+    fn new() -> Self {
+        Self {
+            hash: constants::MD5_INIT_H,
+            data: [0; Self::BLOCK_SIZE],
+            pending_data: 0,
+            total_data: 0,
+        }
+    }
+
+    fn data(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    fn get_pending(&mut self) -> usize {
+        self.pending_data
+    }
+
+    fn set_pending(&mut self, value: usize) {
+        self.pending_data = value;
+    }
+
+    fn inc_total(&mut self, value: usize) {
+        self.total_data += value;
+    }
+
+    fn get_total(&self) -> usize {
+        self.total_data
+    }
+
+    fn reset(&mut self) {
+        self.hash = constants::MD5_INIT_H;
+        self.pending_data = 0;
+        self.total_data = 0;
+    }
+
+    fn process_block(&mut self, block: &[u8]) {
+        let mut m: [u32; 16] = [0; 16];
+        for (i, int_bytes) in block.chunks_exact(4).enumerate() {
+            m[i] = u32::from_le_bytes(int_bytes.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.hash;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5*i + 1) % 16),
+                2 => (b ^ c ^ d, (3*i + 5) % 16),
+                _ => (c ^ (b | !d), (7*i) % 16),
+            };
+            let f = f.wrapping_add(a)
+                .wrapping_add(constants::MD5_K[i])
+                .wrapping_add(m[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(constants::MD5_S[i]));
+        }
+
+        for (h, v) in self.hash.iter_mut().zip([a, b, c, d]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    fn output(&mut self) -> Vec<u8> {
+        let mut output: Vec<u8> = Vec::new();
+        for word in self.hash {
+            output.extend_from_slice(&word.to_le_bytes());
+        }
+        output
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Md5;
+    use crate::hash::Hash;
+    use crate::mac::{Hmac,Mac};
+    use crate::utils::decode_hex;
+
+    const LONG_MSG_SIZE: usize = 4500;
+
+    #[test]
+    fn md5() {
+        let mut h = Md5::new();
+        assert_eq!(h.digest(),
+                   decode_hex("d41d8cd98f00b204e9800998ecf8427e").unwrap());
+        h.update("abc".as_bytes());
+        assert_eq!(h.digest(),
+                   decode_hex("900150983cd24fb0d6963f7d28e17f72").unwrap());
+
+        let long_msg: Vec<u8> = (0..LONG_MSG_SIZE).map(|i| i as u8).collect();
+        for chunk in long_msg.chunks(700) {
+            h.update(chunk);
+        }
+        assert_eq!(h.digest(),
+                   decode_hex("483b46284118db4f9d72e47a5cf14331").unwrap());
+    }
+
+    #[test]
+    fn hmac_md5() {
+        // RFC 2104 test vector
+        let mut hmac = Hmac::<Md5>::new(&[0x0b; 16]).expect("Invalid key");
+        hmac.update("Hi There".as_bytes());
+        let expected = decode_hex("9294727a3638bb1c13f48ef8158bfc9d").unwrap();
+        assert_eq!(hmac.generate(), expected);
+    }
+}
@@ -0,0 +1,107 @@
+use super::Hash;
+
+
+// Largest block of the implementors (SHA-512), for buffering on the stack
+const MAX_BLOCK_SIZE: usize = 128;
+
+
+// Merkle-Damgard construction: message buffering, length padding and
+// finalization shared by SHA-1, SHA-2 and MD5
+pub trait MdCommon: Clone {
+    const DIGEST_SIZE: usize;
+    const BLOCK_SIZE: usize;
+    const BIG_ENDIAN: bool = true; // Encoding of the message length
+
+    fn new() -> Self;
+    fn data(&mut self) -> &mut [u8]; // Size MUST BE BLOCK_SIZE
+    fn get_pending(&mut self) -> usize;
+    fn set_pending(&mut self, value: usize);
+    fn inc_total(&mut self, value: usize);
+    fn get_total(&self) -> usize;
+    fn reset(&mut self);
+    fn process_block(&mut self, block: &[u8]);
+    fn output(&mut self) -> Vec<u8>; // Serialized hash state
+
+    fn pad_last_block(&mut self) -> Vec<u8> {
+        // Length field is 64 bits for 512 bit blocks, 128 bits for 1024 bit
+        let length_size = Self::BLOCK_SIZE / 8;
+        let pending = self.get_pending();
+        let block = &self.data()[..pending];
+        assert!(block.len() <= Self::BLOCK_SIZE);
+
+        let mut padded = Vec::with_capacity(Self::BLOCK_SIZE);
+        padded.extend_from_slice(block);
+        padded.push(0x80);
+
+        let k: usize = if block.len() < Self::BLOCK_SIZE - length_size {
+            Self::BLOCK_SIZE - block.len() - 1 - length_size
+        } else {
+            Self::BLOCK_SIZE*2 - block.len() - 1 - length_size
+        };
+        for _ in 0..k {
+            padded.push(0)
+        }
+
+        let block_len = block.len();
+        let length = ((self.get_total() + block_len) * 8) as u128;
+        if Self::BIG_ENDIAN {
+            padded.extend_from_slice(&length.to_be_bytes()[16-length_size..]);
+        } else {
+            padded.extend_from_slice(&length.to_le_bytes()[..length_size]);
+        }
+        padded
+    }
+}
+
+impl <T: MdCommon> Hash for T {
+    const DIGEST_SIZE: usize = <T as MdCommon>::DIGEST_SIZE;
+    const BLOCK_SIZE: usize = <T as MdCommon>::BLOCK_SIZE;
+
+    fn new() -> Self {
+        <T as MdCommon>::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let pending = self.get_pending();
+        if pending + data.len() < Self::BLOCK_SIZE {
+            self.data()[pending..pending+data.len()].copy_from_slice(data);
+            self.set_pending(pending + data.len());
+            return;
+        }
+
+        // Completed in a copy, process_block can't borrow the state's buffer
+        const { assert!(Self::BLOCK_SIZE <= MAX_BLOCK_SIZE) };
+        let missing_data = Self::BLOCK_SIZE - pending;
+        let mut buffer = [0; MAX_BLOCK_SIZE];
+        let block = &mut buffer[..Self::BLOCK_SIZE];
+        block.copy_from_slice(self.data());
+        block[pending..].copy_from_slice(&data[..missing_data]);
+        self.process_block(block);
+        self.inc_total(Self::BLOCK_SIZE);
+
+        let block_iter = data[missing_data..].chunks_exact(Self::BLOCK_SIZE);
+        let rem = block_iter.remainder();
+        for block in block_iter {
+            self.process_block(block);
+            self.inc_total(Self::BLOCK_SIZE);
+        }
+
+        self.set_pending(rem.len());
+        self.data()[..rem.len()].copy_from_slice(rem);
+    }
+
+    fn digest(&mut self) -> Vec<u8> {
+        let padded = self.pad_last_block();
+        for block in padded.chunks_exact(Self::BLOCK_SIZE) {
+            self.process_block(block);
+        }
+        let mut digest = self.output();
+        digest.truncate(Self::DIGEST_SIZE);
+        self.reset();
+        digest
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
@@ -0,0 +1,131 @@
+use super::md_common::MdCommon;
+use super::sha1_constants as constants;
+
+
+#[derive(Clone)]
+pub struct Sha1 {
+    hash: [u32; 5],
+    data: [u8; constants::SHA1_BLOCK_SIZE],
+    pending_data: usize,
+    total_data: usize,
+}
+
+impl MdCommon for Sha1 {
+    const DIGEST_SIZE: usize = constants::SHA1_DIGEST_SIZE;
+    const BLOCK_SIZE: usize = constants::SHA1_BLOCK_SIZE;
+
+    fn new() -> Self {
+        Self {
+            hash: constants::SHA1_INIT_H,
+            data: [0; Self::BLOCK_SIZE],
+            pending_data: 0,
+            total_data: 0,
+        }
+    }
+
+    fn data(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    fn get_pending(&mut self) -> usize {
+        self.pending_data
+    }
+
+    fn set_pending(&mut self, value: usize) {
+        self.pending_data = value;
+    }
+
+    fn inc_total(&mut self, value: usize) {
+        self.total_data += value;
+    }
+
+    fn get_total(&self) -> usize {
+        self.total_data
+    }
+
+    fn reset(&mut self) {
+        self.hash = constants::SHA1_INIT_H;
+        self.pending_data = 0;
+        self.total_data = 0;
+    }
+
+    fn process_block(&mut self, block: &[u8]) {
+        // Message schedule (W)
+        let mut w: [u32; 80] = [0; 80];
+        for (i, int_bytes) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(int_bytes.try_into().unwrap());
+        }
+        for i in 16..80 {
+            w[i] = (w[i-3] ^ w[i-8] ^ w[i-14] ^ w[i-16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.hash;
+        for (i, &w) in w.iter().enumerate() {
+            let f = match i / 20 {
+                0 => (b & c) | (!b & d),         // Ch
+                2 => (b & c) | (b & d) | (c & d), // Maj
+                _ => b ^ c ^ d,                   // Parity
+            };
+            let temp = a.rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(constants::SHA1_K[i / 20])
+                .wrapping_add(w);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (h, v) in self.hash.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    fn output(&mut self) -> Vec<u8> {
+        let mut output: Vec<u8> = Vec::new();
+        for word in self.hash {
+            output.extend_from_slice(&word.to_be_bytes());
+        }
+        output
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Sha1;
+    use crate::hash::Hash;
+    use crate::mac::{Hmac,Mac};
+    use crate::utils::decode_hex;
+
+    const LONG_MSG_SIZE: usize = 4500;
+
+    #[test]
+    fn sha1() {
+        let mut h = Sha1::new();
+        h.update("abc".as_bytes());
+        let expected = decode_hex("a9993e364706816aba3e25717850c26c9cd0d89d")
+            .unwrap();
+        assert_eq!(h.digest(), expected);
+
+        let long_msg: Vec<u8> = (0..LONG_MSG_SIZE).map(|i| i as u8).collect();
+        for chunk in long_msg.chunks(700) {
+            h.update(chunk);
+        }
+        let expected = decode_hex("5fede1fd4d17b3726be88dfb007ea3f1b2e3c056")
+            .unwrap();
+        assert_eq!(h.digest(), expected);
+    }
+
+    #[test]
+    fn hmac_sha1() {
+        // RFC 2202 test case 1
+        let mut hmac = Hmac::<Sha1>::new(&[0x0b; 20]).expect("Invalid key");
+        hmac.update("Hi There".as_bytes());
+        let expected = decode_hex("b617318655057264e28bc0b6fb378c8ef146be00")
+            .unwrap();
+        assert_eq!(hmac.generate(), expected);
+    }
+}
@@ -0,0 +1,10 @@
+pub const SHA1_DIGEST_SIZE: usize = 20;
+pub const SHA1_BLOCK_SIZE: usize = 64;
+
+// Initial hash values
+pub const SHA1_INIT_H: [u32; 5] = [
+    0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0,
+];
+
+// Round constants, one for each group of 20 rounds
+pub const SHA1_K: [u32; 4] = [0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xca62c1d6];
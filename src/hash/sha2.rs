@@ -1,4 +1,5 @@
 use super::sha2_constants as constants;
+use super::md_common::MdCommon;
 use super::sha2_common::ShaCommon;
 
 
@@ -6,6 +7,7 @@ macro_rules! sha_impl {
     ( $name:ident, $type:ty, $dig_s:expr, $init_h:expr, $block_s:expr,
       $w_len:expr, $k_const:expr ) =>
     {
+        #[derive(Clone)]
         pub struct $name {
             hash: Box<[$type; 8]>,
             data: [u8; $block_s],
@@ -15,11 +17,18 @@ macro_rules! sha_impl {
 
         impl ShaCommon for $name {
             type T = $type;
-            const DIGEST_SIZE: usize = $dig_s;
-            const BLOCK_SIZE: usize = $block_s;
             const W_LENGTH: usize = $w_len;
             const K_CONST: &'static[Self::T] = &$k_const;
 
+            fn hash(&mut self) -> &mut [Self::T; 8] {
+                &mut self.hash
+            }
+        }
+
+        impl MdCommon for $name {
+            const DIGEST_SIZE: usize = $dig_s;
+            const BLOCK_SIZE: usize = $block_s;
+
             fn new() -> Self {
                 Self {
                     hash: Box::new($init_h),
@@ -29,9 +38,6 @@ macro_rules! sha_impl {
                 }
             }
 
-            fn hash(&mut self) -> &mut [Self::T; 8] {
-                &mut self.hash
-            }
             fn data(&mut self) -> &mut [u8] {
                 &mut self.data
             }
@@ -57,6 +63,18 @@ macro_rules! sha_impl {
                 self.pending_data = 0;
                 self.total_data = 0;
             }
+
+            fn process_block(&mut self, block: &[u8]) {
+                self.compress(block);
+            }
+
+            fn output(&mut self) -> Vec<u8> {
+                let mut output: Vec<u8> = Vec::new();
+                for word in self.hash.iter() {
+                    output.extend_from_slice(word.to_be_bytes().as_slice());
+                }
+                output
+            }
         }
     }
 }
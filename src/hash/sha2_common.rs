@@ -1,8 +1,5 @@
 use std::ops::{BitAnd, BitXor, Not};
 
-use super::Hash;
-use super::sha2_constants::SHA256_BLOCK_SIZE;
-
 
 pub trait ShaUint: Copy
              + BitAnd<Output = Self>
@@ -24,7 +21,6 @@ pub trait ShaUint: Copy
     fn v1(self) -> Self;
 
     fn wrapping_add(self, lhs: Self) -> Self;
-    fn to_be_bytes(self) -> Vec<u8>;
     fn from_be_bytes(bytes: &[u8]) -> Self;
 }
 
@@ -52,10 +48,6 @@ impl ShaUint for u32 {
     fn from_be_bytes(bytes: &[u8]) -> Self {
         u32::from_be_bytes(bytes.try_into().unwrap())
     }
-
-    fn to_be_bytes(self) -> Vec<u8> {
-        u32::to_be_bytes(self).to_vec()
-    }
 }
 
 impl ShaUint for u64 {
@@ -82,31 +74,18 @@ impl ShaUint for u64 {
     fn from_be_bytes(bytes: &[u8]) -> Self {
         u64::from_be_bytes(bytes.try_into().unwrap())
     }
-
-    fn to_be_bytes(self) -> Vec<u8> {
-        u64::to_be_bytes(self).to_vec()
-    }
 }
 
 
 pub trait ShaCommon {
     type T: ShaUint + 'static;
 
-    const DIGEST_SIZE: usize;
-    const BLOCK_SIZE: usize;
     const W_LENGTH: usize;
     const K_CONST: &'static[Self::T];
 
-    fn new() -> Self;
     fn hash(&mut self) -> &mut [Self::T; 8];
-    fn data(&mut self) -> &mut [u8]; // Size MUST BE BLOCK_SIZE
-    fn get_pending(&mut self) -> usize;
-    fn set_pending(&mut self, value: usize);
-    fn inc_total(&mut self, value: usize);
-    fn get_total(&self) -> usize;
-    fn reset(&mut self);
-
-    fn process_block(&mut self, block: &[u8]) {
+
+    fn compress(&mut self, block: &[u8]) {
         // Message schedule (W)
         let mut w: Vec<Self::T> = Vec::with_capacity(Self::W_LENGTH);
 
@@ -122,15 +101,15 @@ pub trait ShaCommon {
         }
 
         // Working variables: a(0), b(1), c(2), d(3), e(4), f(5), g(6), h(7)
-        let mut v = self.hash().clone();
+        let mut v = *self.hash();
 
-        for i in 0..Self::W_LENGTH {
+        for (&k, &w) in Self::K_CONST.iter().zip(w.iter()) {
             // t1 = h + s1(e) + ch(e, f, g) + K[t] +  w[t]
             let t1: Self::T = v[7]
                 .wrapping_add(Self::T::s1(v[4]))
                 .wrapping_add(Self::T::ch(v[4], v[5], v[6]))
-                .wrapping_add(Self::K_CONST[i])
-                .wrapping_add(w[i]);
+                .wrapping_add(k)
+                .wrapping_add(w);
             // t2 = s0(a) + maj(a, b, c);
             let t2: Self::T = Self::T::s0(v[0])
                 .wrapping_add(Self::T::maj(v[0], v[1], v[2]));
@@ -145,92 +124,8 @@ pub trait ShaCommon {
             v[0] = t1.wrapping_add(t2);   // a = t1 + t2;
         }
 
-        for i in 0..v.len() {
-            self.hash()[i] = self.hash()[i].wrapping_add(v[i]);
-        }
-    }
-
-    fn pad_last_block(&mut self) -> Vec<u8> {
-        let pending = self.get_pending();
-        let block = &self.data()[..pending];
-        assert!(block.len() <= Self::BLOCK_SIZE);
-
-        let mut padded = Vec::with_capacity(Self::BLOCK_SIZE);
-        padded.extend_from_slice(block);
-        padded.push(0x80);
-
-        let k: usize;
-        if block.len() < Self::BLOCK_SIZE - Self::BLOCK_SIZE/8 {
-            k = Self::BLOCK_SIZE - block.len() - 1 - Self::BLOCK_SIZE/8;
-        } else {
-            k = Self::BLOCK_SIZE*2 - block.len() - 1 - Self::BLOCK_SIZE/8;
-        }
-        for _ in 0..k {
-            padded.push(0)
-        }
-
-        let block_len = block.len();
-        let total_data = self.get_total();
-        if Self::BLOCK_SIZE == SHA256_BLOCK_SIZE {
-            let length = (((total_data+block_len)*8) as u64).to_be_bytes();
-            padded.extend_from_slice(&length);
-        } else {
-            let length = (((total_data+block_len)*8) as u128).to_be_bytes();
-            padded.extend_from_slice(&length);
-        }
-        padded
-    }
-}
-
-impl <T: ShaCommon> Hash for T {
-    const DIGEST_SIZE: usize = <T as ShaCommon>::DIGEST_SIZE;
-    const BLOCK_SIZE: usize = <T as ShaCommon>::BLOCK_SIZE;
-
-    fn new() -> Self {
-        <T as ShaCommon>::new()
-    }
-
-    fn update(&mut self, data: &[u8]) {
-        let pending = self.get_pending();
-        if pending + data.len() < Self::BLOCK_SIZE {
-            self.data()[pending..pending+data.len()].copy_from_slice(data);
-            self.set_pending(pending + data.len());
-            return;
-        }
-
-        let missing_data = Self::BLOCK_SIZE - pending;
-        //TODO: Fix!! unsafe to dereference data?
-        let mut old_data = self.data().to_vec();
-        old_data[pending..].copy_from_slice(&data[..missing_data]);
-        self.process_block(&old_data);
-        self.inc_total(Self::BLOCK_SIZE);
-
-        let block_iter = data[missing_data..].chunks_exact(Self::BLOCK_SIZE);
-        let rem = block_iter.remainder();
-        for block in block_iter {
-            self.process_block(block);
-            self.inc_total(Self::BLOCK_SIZE);
+        for (h, v) in self.hash().iter_mut().zip(v) {
+            *h = h.wrapping_add(v);
         }
-
-        self.set_pending(rem.len());
-        self.data()[..rem.len()].copy_from_slice(rem);
-    }
-
-    fn digest(&mut self) -> Vec<u8> {
-        let padded = self.pad_last_block();
-        for block in padded.chunks_exact(Self::BLOCK_SIZE) {
-            self.process_block(block);
-        }
-        let mut digest: Vec<u8> = Vec::new();
-        for word in self.hash() {
-            digest.extend_from_slice(word.to_be_bytes().as_slice());
-        }
-        digest.truncate(Self::DIGEST_SIZE);
-        self.reset();
-        digest
-    }
-
-    fn reset(&mut self) {
-        self.reset();
     }
 }
@@ -0,0 +1,241 @@
+use super::sha3_constants as constants;
+use super::sha3_common::Keccak;
+use super::{Hash,Xof};
+
+
+macro_rules! sha3_impl {
+    ( $name:ident, $dig_s:expr, $rate:expr, $pad:expr ) =>
+    {
+        #[derive(Clone)]
+        pub struct $name {
+            sponge: Keccak,
+        }
+
+        impl Hash for $name {
+            const DIGEST_SIZE: usize = $dig_s;
+            const BLOCK_SIZE: usize = $rate;
+
+            fn new() -> Self {
+                Self { sponge: Keccak::new($rate, $pad) }
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                self.sponge.absorb(data);
+            }
+
+            fn digest(&mut self) -> Vec<u8> {
+                let mut digest = vec![0; Self::DIGEST_SIZE];
+                self.sponge.squeeze(&mut digest);
+                self.sponge.reset();
+                digest
+            }
+
+            fn reset(&mut self) {
+                self.sponge.reset();
+            }
+        }
+    }
+}
+
+macro_rules! shake_impl {
+    ( $name:ident, $rate:expr ) =>
+    {
+        #[derive(Clone)]
+        pub struct $name {
+            sponge: Keccak,
+        }
+
+        impl Xof for $name {
+            const BLOCK_SIZE: usize = $rate;
+
+            fn new() -> Self {
+                Self { sponge: Keccak::new($rate, constants::SHAKE_PAD) }
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                self.sponge.absorb(data);
+            }
+
+            fn squeeze(&mut self, len: usize) -> Vec<u8> {
+                let mut output = vec![0; len];
+                self.sponge.squeeze(&mut output);
+                output
+            }
+
+            fn reset(&mut self) {
+                self.sponge.reset();
+            }
+        }
+    }
+}
+
+
+sha3_impl!(Sha3_224, constants::SHA3_224_DIGEST_SIZE, constants::SHA3_224_RATE,
+           constants::SHA3_PAD);
+
+sha3_impl!(Sha3_256, constants::SHA3_256_DIGEST_SIZE, constants::SHA3_256_RATE,
+           constants::SHA3_PAD);
+
+sha3_impl!(Sha3_384, constants::SHA3_384_DIGEST_SIZE, constants::SHA3_384_RATE,
+           constants::SHA3_PAD);
+
+sha3_impl!(Sha3_512, constants::SHA3_512_DIGEST_SIZE, constants::SHA3_512_RATE,
+           constants::SHA3_PAD);
+
+// Original Keccak submission padding, as used by Ethereum
+sha3_impl!(Keccak256, constants::SHA3_256_DIGEST_SIZE,
+           constants::SHA3_256_RATE, constants::KECCAK_PAD);
+
+shake_impl!(Shake128, constants::SHAKE128_RATE);
+
+shake_impl!(Shake256, constants::SHAKE256_RATE);
+
+
+#[cfg(test)]
+mod tests {
+    use crate::hash as hash;
+    use crate::hash::{Hash,Xof};
+    use crate::mac::{Hmac,Mac};
+    use crate::utils::decode_hex;
+
+    const SHORT_MSG: &[u8] = "abc".as_bytes();
+    const LONG_MSG_SIZE: usize = 4500;
+
+    fn long_msg() -> Vec<u8> {
+        let mut msg = Vec::with_capacity(LONG_MSG_SIZE);
+        for i in 0..LONG_MSG_SIZE {
+            msg.push(i as u8);
+        }
+        msg
+    }
+
+    fn test_sha3(h: &mut impl Hash, expected_s: &[u8], expected_l: &[u8]) {
+        h.update(SHORT_MSG);
+        let digest = h.digest();
+        assert_eq!(digest, expected_s);
+        let long_msg = long_msg();
+        let iter = long_msg.chunks_exact(700);
+        let rem = iter.remainder();
+        for chunk in iter {
+            h.update(chunk);
+        }
+        h.update(rem);
+        let digest = h.digest();
+        assert_eq!(digest, expected_l);
+    }
+
+    #[test]
+    fn sha3_224() {
+        let mut h = hash::Sha3_224::new();
+        let expected_s = decode_hex("e642824c3f8cf24ad09234ee7d3c766fc9a3a516\
+                                    8d0c94ad73b46fdf").unwrap();
+        let expected_l = decode_hex("359cd2773898fe0fb5941d9dd897890b1fcb8f14\
+                                    174a4908d3791727").unwrap();
+        test_sha3(&mut h, &expected_s, &expected_l);
+    }
+
+    #[test]
+    fn sha3_256() {
+        let mut h = hash::Sha3_256::new();
+        let expected_s = decode_hex("3a985da74fe225b2045c172d6bd390bd855f086e\
+                                    3e9d525b46bfe24511431532").unwrap();
+        let expected_l = decode_hex("a5622e487153df8ffbbc2d8e7b2a98249cc532dc\
+                                    5dafbc827fa887188a9357ae").unwrap();
+        test_sha3(&mut h, &expected_s, &expected_l);
+    }
+
+    #[test]
+    fn sha3_384() {
+        let mut h = hash::Sha3_384::new();
+        let expected_s = decode_hex("ec01498288516fc926459f58e2c6ad8df9b473cb\
+                                    0fc08c2596da7cf0e49be4b298d88cea927ac7f5\
+                                    39f1edf228376d25").unwrap();
+        let expected_l = decode_hex("74e6c1e4d745b3c59904cc91783160e21a8ecee0\
+                                    fce8862450a3e94ebe3966908539078a20b80952\
+                                    4d29ce5d66673f49").unwrap();
+        test_sha3(&mut h, &expected_s, &expected_l);
+    }
+
+    #[test]
+    fn sha3_512() {
+        let mut h = hash::Sha3_512::new();
+        let expected_s = decode_hex("b751850b1a57168a5693cd924b6b096e08f62182\
+                                    7444f70d884f5d0240d2712e10e116e9192af3c9\
+                                    1a7ec57647e3934057340b4cf408d5a56592f827\
+                                    4eec53f0").unwrap();
+        let expected_l = decode_hex("235639e60098b838a8c260c4699412acf2d8b1d8\
+                                    b7e1e1dd3e30b0fc64c910436330a53f8819b863\
+                                    73d991b6dab13bbd31c88e85ed89847925130b65\
+                                    413f9a67").unwrap();
+        test_sha3(&mut h, &expected_s, &expected_l);
+    }
+
+    #[test]
+    fn keccak_256() {
+        let mut h = hash::Keccak256::new();
+        let expected = decode_hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca\
+                                   82273b7bfad8045d85a470").unwrap();
+        assert_eq!(h.digest(), expected);
+        h.update(SHORT_MSG);
+        let expected = decode_hex("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a\
+                                   64a036ec44f58fa12d6c45").unwrap();
+        assert_eq!(h.digest(), expected);
+    }
+
+    #[test]
+    fn shake128() {
+        let mut h = hash::Shake128::new();
+        let expected = decode_hex("7f9c2ba4e88f827d616045507605853ed73b8093f6\
+                                   efbc88eb1a6eacfa66ef26").unwrap();
+        assert_eq!(h.squeeze(32), expected);
+
+        // Output is read across several permutations
+        h.reset();
+        h.update(&long_msg());
+        let expected = decode_hex("19f3e9c61d01ce902cf0e9191f5d94ce23e634f082\
+                                   9bdb13c0bb2d2d29e6eb513eed419f3cbd2c10d18d\
+                                   cceb153216ff79e7ab813053c67798cedcf2702956\
+                                   cbc54fdb50a666a74b2fd94923bb03512d01826acf\
+                                   a77d64a341055c1202883da86fded3c1e33fc0bbd2\
+                                   dc2c16f69fb6e3fbd1e5df5656aec1c439ecbaa589\
+                                   76fd600b5be0c81078e152f275317da85e982d233f\
+                                   8a2d780d6dad97b9d845bbb96a0a760b0836ea8e3d\
+                                   5fc3c479665579ef1a123022432eb9fe73e9a4bb82\
+                                   94793620eb176d644e602a").unwrap();
+        let mut output = h.squeeze(100);
+        output.extend(h.squeeze(100));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot absorb after squeezing")]
+    fn shake_update_after_squeeze() {
+        let mut h = hash::Shake128::new();
+        h.squeeze(16);
+        h.update(b"abc");
+    }
+
+    #[test]
+    fn shake256() {
+        let mut h = hash::Shake256::new();
+        let expected = decode_hex("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62\
+                                   b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05\
+                                   019d67b592f6fc821c49479ab48640292eacb3b7c4\
+                                   be").unwrap();
+        assert_eq!(h.squeeze(64), expected);
+        h.reset();
+        h.update(SHORT_MSG);
+        let expected = decode_hex("483366601360a8771c6863080cc4114d").unwrap();
+        assert_eq!(h.squeeze(16), expected);
+    }
+
+    #[test]
+    fn hmac_sha3() {
+        let mut hmac = Hmac::<hash::Sha3_256>::new("key".as_bytes())
+            .expect("Invalid key");
+        hmac.update("The quick brown fox jumps over the lazy dog".as_bytes());
+        let expected = decode_hex("8c6e0683409427f8931711b10ca92a506eb1fafa48\
+                                   fadd66d76126f47ac2c333").unwrap();
+        assert_eq!(hmac.generate(), expected);
+    }
+}
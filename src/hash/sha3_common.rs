@@ -0,0 +1,108 @@
+use super::sha3_constants as constants;
+
+
+// Keccak sponge, absorbs rate bytes per permutation
+#[derive(Clone)]
+pub struct Keccak {
+    state: [u64; constants::STATE_SIZE],
+    rate: usize,
+    pad: u8,
+    position: usize,
+    squeezing: bool,
+}
+
+impl Keccak {
+    pub fn new(rate: usize, pad: u8) -> Self {
+        Self {
+            state: [0; constants::STATE_SIZE],
+            rate,
+            pad,
+            position: 0,
+            squeezing: false,
+        }
+    }
+
+    pub fn absorb(&mut self, data: &[u8]) {
+        assert!(!self.squeezing, "Cannot absorb after squeezing");
+        for &byte in data {
+            self.xor_byte(self.position, byte);
+            self.position += 1;
+            if self.position == self.rate {
+                keccak_f(&mut self.state);
+                self.position = 0;
+            }
+        }
+    }
+
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        if !self.squeezing {
+            self.xor_byte(self.position, self.pad);
+            self.xor_byte(self.rate - 1, 0x80);
+            keccak_f(&mut self.state);
+            self.position = 0;
+            self.squeezing = true;
+        }
+
+        for byte in output {
+            if self.position == self.rate {
+                keccak_f(&mut self.state);
+                self.position = 0;
+            }
+            *byte = self.get_byte(self.position);
+            self.position += 1;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = [0; constants::STATE_SIZE];
+        self.position = 0;
+        self.squeezing = false;
+    }
+
+    // Lanes are little endian
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index/8] ^= (byte as u64) << (8 * (index%8));
+    }
+
+    fn get_byte(&self, index: usize) -> u8 {
+        (self.state[index/8] >> (8 * (index%8))) as u8
+    }
+}
+
+
+pub fn keccak_f(state: &mut [u64; constants::STATE_SIZE]) {
+    for rc in constants::RC {
+        // Theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x+5] ^ state[x+10] ^ state[x+15]
+                 ^ state[x+20];
+        }
+        for x in 0..5 {
+            let d = c[(x+4) % 5] ^ c[(x+1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5*y] ^= d;
+            }
+        }
+
+        // Rho and pi, B[y, 2x+3y] = rot(A[x, y], r[x, y])
+        let mut b = [0u64; constants::STATE_SIZE];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5*((2*x + 3*y) % 5)] =
+                    state[x + 5*y].rotate_left(constants::RHO[x + 5*y]);
+            }
+        }
+
+        // Chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5*y] = b[x + 5*y]
+                               ^ (!b[(x+1)%5 + 5*y] & b[(x+2)%5 + 5*y]);
+            }
+        }
+
+        // Iota
+        state[0] ^= rc;
+    }
+}
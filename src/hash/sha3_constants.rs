@@ -0,0 +1,48 @@
+// Digest sizes
+pub const SHA3_224_DIGEST_SIZE: usize = 28;
+pub const SHA3_256_DIGEST_SIZE: usize = 32;
+pub const SHA3_384_DIGEST_SIZE: usize = 48;
+pub const SHA3_512_DIGEST_SIZE: usize = 64;
+
+// Rates (block sizes), 200 bytes of state minus twice the digest size
+pub const SHA3_224_RATE: usize = 144;
+pub const SHA3_256_RATE: usize = 136;
+pub const SHA3_384_RATE: usize = 104;
+pub const SHA3_512_RATE: usize = 72;
+pub const SHAKE128_RATE: usize = 168;
+pub const SHAKE256_RATE: usize = 136;
+
+// Domain separation suffix, including the first bit of the pad10*1 rule
+pub const SHA3_PAD: u8 = 0x06;
+pub const SHAKE_PAD: u8 = 0x1F;
+pub const KECCAK_PAD: u8 = 0x01;
+
+// State size in 64 bit lanes
+pub const STATE_SIZE: usize = 25;
+
+pub const ROUNDS: usize = 24;
+
+// Iota round constants
+pub const RC: [u64; ROUNDS] = [
+    0x0000000000000001, 0x0000000000008082,
+    0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001,
+    0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088,
+    0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b,
+    0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080,
+    0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080,
+    0x0000000080000001, 0x8000000080008008,
+];
+
+// Rho rotation offsets, indexed by x + 5*y
+pub const RHO: [u32; STATE_SIZE] = [
+     0,  1, 62, 28, 27,
+    36, 44,  6, 55, 20,
+     3, 10, 43, 25, 39,
+    41, 45, 15, 21,  8,
+    18,  2, 61, 56, 14,
+];
@@ -0,0 +1,5 @@
+mod hkdf;
+mod pbkdf2;
+
+pub use hkdf::{hkdf,hkdf_extract,hkdf_expand};
+pub use pbkdf2::pbkdf2;
@@ -0,0 +1,122 @@
+use crate::error::InvalidOutputLen;
+use crate::hash::Hash;
+use crate::mac::{Hmac,Mac};
+
+
+// RFC 5869, expand can produce at most 255 blocks of the digest size
+const MAX_BLOCKS: usize = 255;
+
+
+// An empty salt is equivalent to a salt of DIGEST_SIZE zeros
+pub fn hkdf_extract<H: Hash>(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    let mut hmac = Hmac::<H>::new(salt).expect("Invalid key");
+    hmac.update(ikm);
+    hmac.generate()
+}
+
+pub fn hkdf_expand<H: Hash>(prk: &[u8], info: &[u8], out_len: usize)
+        -> Result<Vec<u8>, InvalidOutputLen> {
+    if out_len > MAX_BLOCKS * H::DIGEST_SIZE {
+        return Err(InvalidOutputLen);
+    }
+
+    let mut hmac = Hmac::<H>::new(prk).expect("Invalid key");
+    let mut okm: Vec<u8> = Vec::with_capacity(out_len);
+    let mut t: Vec<u8> = Vec::new();
+    let mut counter: u8 = 1;
+
+    while okm.len() < out_len {
+        hmac.update(&t);
+        hmac.update(info);
+        hmac.update(&[counter]);
+        t = hmac.generate();
+
+        let missing = out_len - okm.len();
+        okm.extend_from_slice(&t[..missing.min(t.len())]);
+        counter = counter.wrapping_add(1);
+    }
+
+    Ok(okm)
+}
+
+pub fn hkdf<H: Hash>(salt: &[u8], ikm: &[u8], info: &[u8], out_len: usize)
+        -> Result<Vec<u8>, InvalidOutputLen> {
+    let prk = hkdf_extract::<H>(salt, ikm);
+    hkdf_expand::<H>(&prk, info, out_len)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{hkdf,hkdf_extract,hkdf_expand};
+    use crate::hash::{Sha1,Sha256};
+    use crate::utils::decode_hex;
+
+    fn range(start: u8, end: u8) -> Vec<u8> {
+        (start..end).collect()
+    }
+
+    #[test]
+    fn hkdf_sha256() {
+        // RFC 5869 test case 1
+        let ikm = [0x0b; 22];
+        let salt = range(0x00, 0x0d);
+        let info = range(0xf0, 0xfa);
+        let expected_prk = decode_hex("077709362c2e32df0ddc3f0dc47bba6390b6c73\
+                                       bb50f9c3122ec844ad7c2b3e5").unwrap();
+        let expected = decode_hex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf\
+                                   1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+            .unwrap();
+        let prk = hkdf_extract::<Sha256>(&salt, &ikm);
+        assert_eq!(prk, expected_prk);
+        assert_eq!(hkdf_expand::<Sha256>(&prk, &info, 42).unwrap(), expected);
+        assert_eq!(hkdf::<Sha256>(&salt, &ikm, &info, 42).unwrap(), expected);
+    }
+
+    #[test]
+    fn hkdf_sha256_long() {
+        // RFC 5869 test case 2
+        let ikm = range(0x00, 0x50);
+        let salt = range(0x60, 0xb0);
+        let info: Vec<u8> = (0xb0..=0xff).collect();
+        let expected = decode_hex("b11e398dc80327a1c8e7f78c596a49344f012eda2d\
+                                   4efad8a050cc4c19afa97c59045a99cac7827271cb\
+                                   41c65e590e09da3275600c2f09b8367793a9aca3db\
+                                   71cc30c58179ec3e87c14c01d5c1f3434f1d87")
+            .unwrap();
+        assert_eq!(hkdf::<Sha256>(&salt, &ikm, &info, 82).unwrap(), expected);
+    }
+
+    #[test]
+    fn hkdf_sha256_empty() {
+        // RFC 5869 test case 3
+        let ikm = [0x0b; 22];
+        let expected = decode_hex("8da4e775a563c18f715f802a063c5a31b8a11f5c5e\
+                                   e1879ec3454e5f3c738d2d9d201395faa4b61a96c8")
+            .unwrap();
+        assert_eq!(hkdf::<Sha256>(&[], &ikm, &[], 42).unwrap(), expected);
+    }
+
+    #[test]
+    fn hkdf_sha1() {
+        // RFC 5869 test case 4
+        let ikm = [0x0b; 11];
+        let salt = range(0x00, 0x0d);
+        let info = range(0xf0, 0xfa);
+        let expected_prk = decode_hex("9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba24\
+                                       3").unwrap();
+        let expected = decode_hex("085a01ea1b10f36933068b56efa5ad81a4f14b822f\
+                                   5b091568a9cdd4f155fda2c22e422478d305f3f896")
+            .unwrap();
+        assert_eq!(hkdf_extract::<Sha1>(&salt, &ikm), expected_prk);
+        assert_eq!(hkdf::<Sha1>(&salt, &ikm, &info, 42).unwrap(), expected);
+    }
+
+    #[test]
+    fn hkdf_output_len() {
+        let prk = hkdf_extract::<Sha256>(&[], &[0x0b; 22]);
+        assert_eq!(hkdf_expand::<Sha256>(&prk, &[], 255*32).unwrap().len(),
+                   255*32);
+        assert!(hkdf_expand::<Sha256>(&prk, &[], 255*32 + 1).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::error::{InvalidIterationCount,InvalidOutputLen,KdfError};
+use crate::hash::Hash;
+use crate::mac::{Hmac,Mac};
+use crate::utils;
+
+
+// RFC 8018 section 5.2, HMAC-H is the pseudorandom function
+pub fn pbkdf2<H: Hash>(password: &[u8], salt: &[u8], iterations: u32,
+        out_len: usize) -> Result<Vec<u8>, KdfError> {
+    if iterations == 0 {
+        return Err(InvalidIterationCount.into());
+    }
+    if out_len as u64 > u32::MAX as u64 * H::DIGEST_SIZE as u64 {
+        return Err(InvalidOutputLen.into());
+    }
+
+    let mut hmac = Hmac::<H>::new(password).expect("Invalid key");
+    let mut derived_key: Vec<u8> = Vec::with_capacity(out_len);
+    let mut block_index: u32 = 1;
+
+    while derived_key.len() < out_len {
+        hmac.update(salt);
+        hmac.update(&block_index.to_be_bytes());
+        let mut u = hmac.generate();
+        let mut block = u.clone();
+
+        for _ in 1..iterations {
+            hmac.update(&u);
+            u = hmac.generate();
+            utils::xor_slice(&mut block, &u);
+        }
+
+        let missing = out_len - derived_key.len();
+        derived_key.extend_from_slice(&block[..missing.min(block.len())]);
+        block_index += 1;
+    }
+
+    Ok(derived_key)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::pbkdf2;
+    use crate::cipher::{Aes128Cipher,Cipher};
+    use crate::error::KdfError;
+    use crate::hash::{Sha1,Sha256,Sha512};
+    use crate::utils::decode_hex;
+
+    const PASSWORD: &[u8] = "password".as_bytes();
+    const SALT: &[u8] = "salt".as_bytes();
+    const LONG_PASSWORD: &[u8] = "passwordPASSWORDpassword".as_bytes();
+    const LONG_SALT: &[u8] = "saltSALTsaltSALTsaltSALTsaltSALTsalt".as_bytes();
+
+    #[test]
+    fn pbkdf2_sha1() {
+        // RFC 6070
+        let expected = decode_hex("0c60c80f961f0e71f3a9b524af6012062fe037a6")
+            .unwrap();
+        assert_eq!(pbkdf2::<Sha1>(PASSWORD, SALT, 1, 20).unwrap(), expected);
+        let expected = decode_hex("ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957")
+            .unwrap();
+        assert_eq!(pbkdf2::<Sha1>(PASSWORD, SALT, 2, 20).unwrap(), expected);
+        let expected = decode_hex("4b007901b765489abead49d926f721d065a429c1")
+            .unwrap();
+        assert_eq!(pbkdf2::<Sha1>(PASSWORD, SALT, 4096, 20).unwrap(), expected);
+        let expected = decode_hex("3d2eec4fe41c849b80c8d83662c0e44a8b291a964c\
+                                   f2f07038").unwrap();
+        assert_eq!(pbkdf2::<Sha1>(LONG_PASSWORD, LONG_SALT, 4096, 25).unwrap(),
+                   expected);
+        let expected = decode_hex("56fa6aa75548099dcc37d7f03425e0c3").unwrap();
+        assert_eq!(pbkdf2::<Sha1>("pass\0word".as_bytes(), "sa\0lt".as_bytes(),
+                                  4096, 16).unwrap(), expected);
+    }
+
+    #[test]
+    fn pbkdf2_sha256() {
+        let expected = decode_hex("120fb6cffcf8b32c43e7225256c4f837a86548c92c\
+                                   cc35480805987cb70be17b").unwrap();
+        assert_eq!(pbkdf2::<Sha256>(PASSWORD, SALT, 1, 32).unwrap(), expected);
+        let expected = decode_hex("c5e478d59288c841aa530db6845c4c8d962893a001\
+                                   ce4e11a4963873aa98134a").unwrap();
+        assert_eq!(pbkdf2::<Sha256>(PASSWORD, SALT, 4096, 32).unwrap(),
+                   expected);
+        let expected = decode_hex("348c89dbcbd32b2f32d814b8116e84cf2b17347ebc\
+                                   1800181c4e2a1fb8dd53e1c635518c7dac47e9")
+            .unwrap();
+        assert_eq!(pbkdf2::<Sha256>(LONG_PASSWORD, LONG_SALT, 4096, 40)
+                   .unwrap(), expected);
+    }
+
+    #[test]
+    fn pbkdf2_sha512() {
+        let expected = decode_hex("867f70cf1ade02cff3752599a3a53dc4af34c7a669\
+                                   815ae5d513554e1c8cf252c02d470a285a0501bad9\
+                                   99bfe943c08f050235d7d68b1da55e63f73b60a57f\
+                                   ce").unwrap();
+        assert_eq!(pbkdf2::<Sha512>(PASSWORD, SALT, 1, 64).unwrap(), expected);
+        let expected = decode_hex("d197b1b33db0143e018b12f3d1d1479e6cdebdcc97\
+                                   c5c0f87f6902e072f457b5143f30602641b3d55cd3\
+                                   35988cb36b84376060ecd532e039b742a239434af2\
+                                   d5").unwrap();
+        assert_eq!(pbkdf2::<Sha512>(PASSWORD, SALT, 4096, 64).unwrap(),
+                   expected);
+    }
+
+    #[test]
+    fn pbkdf2_zero_iterations() {
+        assert!(matches!(pbkdf2::<Sha256>(PASSWORD, SALT, 0, 32),
+                         Err(KdfError::InvalidIterationCount(_))));
+    }
+
+    #[test]
+    fn pbkdf2_aes_key() {
+        let key = pbkdf2::<Sha256>(PASSWORD, SALT, 1000, 16).unwrap();
+        assert!(Aes128Cipher::new(&key).is_ok());
+    }
+}
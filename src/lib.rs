@@ -0,0 +1,40 @@
+//! Implementation of some common cryptographic primitives.
+//!
+//! ```
+//! use crypto_rust::prelude::*;
+//! use crypto_rust::modes::cbc;
+//!
+//! let aes = Aes128Cipher::new(b"yellow submarine").unwrap();
+//! let iv = [0; 16];
+//! let encrypted = cbc::encrypt(b"Attack at dawn!", &aes, &iv).unwrap();
+//! let decrypted = cbc::decrypt(&encrypted, &aes, &iv).unwrap();
+//! assert_eq!(decrypted, b"Attack at dawn!");
+//! ```
+
+pub mod aead;
+pub mod cipher;
+pub mod error;
+pub mod hash;
+pub mod kdf;
+pub mod mac;
+pub mod modes;
+mod utils;
+
+pub use aead::Aead;
+pub use cipher::{Cipher,Aes128Cipher,Aes192Cipher,Aes256Cipher,AesBackend,
+                 DesCipher,TripleDesCipher,ChaCha20,XChaCha20};
+pub use hash::{Hash,Xof,Md5,Sha1,Sha224,Sha256,Sha384,Sha512,Sha512_224,
+               Sha512_256,Sha3_224,Sha3_256,Sha3_384,Sha3_512,Keccak256,
+               Shake128,Shake256};
+pub use mac::{Mac,Hmac,Cmac,Poly1305};
+
+
+pub mod prelude {
+    pub use crate::aead::Aead;
+    pub use crate::cipher::{Cipher,Aes128Cipher,Aes192Cipher,Aes256Cipher,
+                            DesCipher,TripleDesCipher,ChaCha20,XChaCha20};
+    pub use crate::hash::{Hash,Xof,Md5,Sha1,Sha224,Sha256,Sha384,Sha512,
+                          Sha512_224,Sha512_256,Sha3_224,Sha3_256,Sha3_384,
+                          Sha3_512,Keccak256,Shake128,Shake256};
+    pub use crate::mac::{Mac,Hmac,Cmac,Poly1305};
+}
@@ -1,7 +1,13 @@
+mod cmac;
 mod hmac;
+mod poly1305;
+pub use cmac::Cmac;
 pub use hmac::Hmac;
+pub use poly1305::Poly1305;
+pub(crate) use cmac::double;
 
 use crate::error::{IncorrectMac, InvalidKeyLen};
+use crate::utils;
 
 
 pub trait Mac {
@@ -9,6 +15,7 @@ pub trait Mac {
 
     fn update(&mut self, data: &[u8]);
 
+    // Resets the MAC so it can be reused with the same key
     fn generate(&mut self) -> Vec<u8>;
 
     fn reset(&mut self);
@@ -18,7 +25,7 @@ pub trait Mac {
         self.reset();
         self.update(data);
         let expected_signature = self.generate();
-        if expected_signature != signature {
+        if !utils::constant_time_eq(&expected_signature, signature) {
             return Err(IncorrectMac);
         }
         Ok(())
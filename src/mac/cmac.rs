@@ -0,0 +1,163 @@
+use crate::cipher::Cipher;
+use crate::error::InvalidKeyLen;
+use crate::utils;
+use super::Mac;
+
+
+// Subkey generation constants for 64 and 128 bit blocks (SP 800-38B 5.3)
+const RB_64: u8 = 0x1B;
+const RB_128: u8 = 0x87;
+
+// The last block is kept buffered, it is processed with a subkey on generate
+pub struct Cmac<C> where C: Cipher {
+    cipher: C,
+    k1: C::Block,
+    k2: C::Block,
+    state: C::Block,
+    buffer: Vec<u8>,
+}
+
+// Multiplication by x in GF(2^n), in place. Only 64 and 128 bit blocks are
+// supported: Cmac checks the block size at compile time and the other users
+// work on 128 bit blocks.
+pub(crate) fn double(block: &mut [u8]) {
+    let rb = match block.len() {
+        8 => RB_64,
+        16 => RB_128,
+        _ => unreachable!("No subkey constant for this block size"),
+    };
+
+    let msb = block[0] >> 7;
+    for i in 0..block.len() {
+        let carry = block.get(i+1).map_or(0, |next| next >> 7);
+        block[i] = (block[i] << 1) | carry;
+    }
+    if msb != 0 {
+        block[block.len()-1] ^= rb;
+    }
+}
+
+impl<C> Cmac<C> where C: Cipher {
+    fn process_buffer(&mut self) {
+        utils::xor_slice(self.state.as_mut(), &self.buffer);
+        self.cipher.encrypt_block(&mut self.state);
+        self.buffer.clear();
+    }
+
+    // The tag as a block, for the modes built on CMAC
+    pub(crate) fn generate_block(&mut self) -> C::Block {
+        if self.buffer.len() == C::BLOCK_SIZE {
+            utils::xor_slice(&mut self.buffer, self.k1.as_ref());
+        } else {
+            self.buffer.push(0x80);
+            self.buffer.resize(C::BLOCK_SIZE, 0);
+            utils::xor_slice(&mut self.buffer, self.k2.as_ref());
+        }
+        self.process_buffer();
+
+        let tag = self.state;
+        self.reset();
+        tag
+    }
+}
+
+impl<C> Mac for Cmac<C> where C: Cipher {
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        const {
+            assert!(C::BLOCK_SIZE == 8 || C::BLOCK_SIZE == 16,
+                    "CMAC only supports 64 and 128 bit block ciphers");
+        }
+        let cipher = C::new(key)?;
+        let mut k1 = C::Block::default();
+        cipher.encrypt_block(&mut k1);
+        double(k1.as_mut());
+        let mut k2 = k1;
+        double(k2.as_mut());
+        let state = C::Block::default();
+        let buffer = Vec::with_capacity(C::BLOCK_SIZE);
+
+        Ok(Self {cipher, k1, k2, state, buffer})
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buffer.len() == C::BLOCK_SIZE {
+                self.process_buffer();
+            }
+            let missing = C::BLOCK_SIZE - self.buffer.len();
+            let (head, tail) = data.split_at(missing.min(data.len()));
+            self.buffer.extend_from_slice(head);
+            data = tail;
+        }
+    }
+
+    fn generate(&mut self) -> Vec<u8> {
+        self.generate_block().as_ref().to_vec()
+    }
+
+    fn reset(&mut self) {
+        self.state = C::Block::default();
+        self.buffer.clear();
+    }
+
+    fn size(&self) -> usize {
+        C::BLOCK_SIZE
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{Cmac,Mac};
+    use crate::cipher::{Aes128Cipher,DesCipher,TripleDesCipher};
+    use crate::utils::decode_hex;
+
+    const MSG: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac\
+                       45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17\
+                       ad2b417be66c3710";
+
+    fn test_cmac(cmac: &mut impl Mac, msg_len: usize, expected: &str) {
+        let msg = decode_hex(MSG).unwrap();
+        let expected = decode_hex(expected).unwrap();
+        cmac.update(&msg[..msg_len]);
+        let tag = cmac.generate();
+        assert_eq!(tag, expected);
+        assert!(cmac.verify(&msg[..msg_len], &tag).is_ok());
+    }
+
+    #[test]
+    fn cmac_aes() {
+        // RFC 4493
+        let key = decode_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let mut cmac = Cmac::<Aes128Cipher>::new(&key).expect("Invalid key");
+        test_cmac(&mut cmac, 0, "bb1d6929e95937287fa37d129b756746");
+        test_cmac(&mut cmac, 16, "070a16b46b4d4144f79bdd9dd04a287c");
+        test_cmac(&mut cmac, 40, "dfa66747de9ae63030ca32611497c827");
+        test_cmac(&mut cmac, 64, "51f0bebf7e3b9d92fc49741779363cfe");
+    }
+
+    #[test]
+    fn cmac_tdes() {
+        // SP 800-38B, three key TDES examples
+        let key = decode_hex("8aa83bf8cbda10620bc1bf19fbb6cd58bc313d4a371ca8b5")
+            .unwrap();
+        let mut cmac = Cmac::<TripleDesCipher>::new(&key).expect("Invalid key");
+        test_cmac(&mut cmac, 0, "b7a688e122ffaf95");
+        test_cmac(&mut cmac, 16, "286d394673448197");
+        test_cmac(&mut cmac, 20, "743ddbe0ce2dc2ed");
+        test_cmac(&mut cmac, 32, "33e6b1092400eae5");
+    }
+
+    #[test]
+    fn cmac_streaming() {
+        let msg = decode_hex(MSG).unwrap();
+        let mut cmac = Cmac::<DesCipher>::new(&[0x2b; 8]).expect("Invalid key");
+        cmac.update(&msg);
+        let tag = cmac.generate();
+        for chunk in msg.chunks(3) {
+            cmac.update(chunk);
+        }
+        assert_eq!(cmac.generate(), tag);
+        assert!(Cmac::<Aes128Cipher>::new(&[0; 8]).is_err());
+    }
+}
@@ -5,62 +5,63 @@ use super::Mac;
 const INNER_PAD_VAL: u8 = 0x36;
 const OUTER_PAD_VAL: u8 = 0x5C;
 
+// Data is streamed into the inner hash, the keyed inner and outer states are
+// computed once and restored after each generate/reset
+#[derive(Clone)]
 pub struct Hmac<H> where H: Hash {
-    hash: H,
-    key: Vec<u8>,
-    msg: Vec<u8>,
+    inner_hash: H,
+    inner_keyed: H,
+    outer_keyed: H,
 }
 
 fn sanitize_key<H: Hash>(key: &[u8]) -> Vec<u8> {
-    let mut hash = H::new();
+    let mut sanitized_key = Vec::<u8>::from(key);
     if key.len() > H::BLOCK_SIZE {
+        let mut hash = H::new();
         hash.update(key);
-        let sanitized_key = hash.digest();
-        return sanitized_key;
+        sanitized_key = hash.digest();
     }
 
-    let mut sanitized_key = Vec::<u8>::from(key);
     while sanitized_key.len() < H::BLOCK_SIZE {
         sanitized_key.push(0);
     }
     sanitized_key
 }
 
+fn keyed_hash<H: Hash>(key: &[u8], pad_val: u8) -> H {
+    let mut pad_key = key.to_vec();
+    for x in pad_key.iter_mut() {
+        *x ^= pad_val;
+    }
+    let mut hash = H::new();
+    hash.update(&pad_key);
+    hash
+}
+
 impl<H> Mac for Hmac<H> where H: Hash {
     fn new(key: &[u8]) -> Result<Self, crate::error::InvalidKeyLen> {
-        let hash = H::new();
         let key = sanitize_key::<H>(key);
-        let msg = Vec::new();
+        let inner_keyed = keyed_hash::<H>(&key, INNER_PAD_VAL);
+        let outer_keyed = keyed_hash::<H>(&key, OUTER_PAD_VAL);
 
-        Ok(Self {hash, key, msg})
+        Ok(Self {inner_hash: inner_keyed.clone(), inner_keyed, outer_keyed})
     }
 
     fn update(&mut self, data: &[u8]) {
-        self.msg.extend_from_slice(data);
+        self.inner_hash.update(data);
     }
 
-
     fn generate(&mut self) -> Vec<u8> {
-        let mut ipad_key = self.key.clone();
-        let mut opad_key = self.key.clone();
-        for i in 0..ipad_key.len() {
-            ipad_key[i] ^= INNER_PAD_VAL;
-            opad_key[i] ^= OUTER_PAD_VAL;
-        }
+        let inner_digest = self.inner_hash.digest();
+        self.reset();
 
-        self.hash.reset();
-        self.hash.update(ipad_key.as_slice());
-        self.hash.update(self.msg.as_slice());
-        let inner_hash = self.hash.digest();
-        self.hash.reset();
-        self.hash.update(opad_key.as_slice());
-        self.hash.update(inner_hash.as_slice());
-        let outer_hash = self.hash.digest();
-        outer_hash
+        let mut outer_hash = self.outer_keyed.clone();
+        outer_hash.update(&inner_digest);
+        outer_hash.digest()
     }
 
     fn reset(&mut self) {
-        self.msg.clear();
+        self.inner_hash = self.inner_keyed.clone();
     }
 
     fn size(&self) -> usize {
@@ -90,4 +91,33 @@ mod tests {
         let result = hmac_sha256.verify(MSG, signature.as_slice());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn hmac_long_key() {
+        // RFC 4231 test case 6, the hashed key is zero padded to the block
+        let expected = decode_hex("60e431591ee0b67f0d8a26aacbf5b77f8e0bc62137\
+                                   28c5140546040f0ee37f54").unwrap();
+        let mut hmac_sha256 = Hmac::<Sha256>::new(&[0xaa; 131])
+            .expect("Invalid key");
+        hmac_sha256.update("Test Using Larger Than Block-Size Key - Hash Key \
+                            First".as_bytes());
+        assert_eq!(hmac_sha256.generate(), expected);
+    }
+
+    #[test]
+    fn hmac_streaming() {
+        let mut hmac_sha256 = Hmac::<Sha256>::new(KEY).expect("Invalid key");
+        for chunk in MSG.chunks(5) {
+            hmac_sha256.update(chunk);
+        }
+        let signature = hmac_sha256.generate();
+
+        // The keyed state is restored after generate
+        hmac_sha256.update(MSG);
+        assert_eq!(hmac_sha256.generate(), signature);
+        hmac_sha256.update("Some other message".as_bytes());
+        hmac_sha256.reset();
+        hmac_sha256.update(MSG);
+        assert_eq!(hmac_sha256.generate(), signature);
+    }
 }
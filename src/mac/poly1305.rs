@@ -0,0 +1,209 @@
+use crate::error::InvalidKeyLen;
+use super::Mac;
+
+
+pub const KEY_SIZE: usize = 32;
+pub const BLOCK_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+
+
+// RFC 8439 one-time authenticator, a key MUST NOT be used for two messages.
+// Arithmetic modulo 2^130 - 5 uses five 26 bit limbs
+pub struct Poly1305 {
+    r: [u32; 5],
+    s: [u32; 4],
+    acc: [u32; 5],
+    buffer: [u8; BLOCK_SIZE],
+    pending: usize,
+}
+
+impl Poly1305 {
+    fn process_block(&mut self, block: &[u8; BLOCK_SIZE], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+
+        // acc += block
+        let t = |i: usize| u32::from_le_bytes(block[i..i+4].try_into().unwrap());
+        let h0 = (self.acc[0] + (t(0) & 0x3ffffff)) as u64;
+        let h1 = (self.acc[1] + ((t(3) >> 2) & 0x3ffffff)) as u64;
+        let h2 = (self.acc[2] + ((t(6) >> 4) & 0x3ffffff)) as u64;
+        let h3 = (self.acc[3] + ((t(9) >> 6) & 0x3ffffff)) as u64;
+        let h4 = (self.acc[4] + ((t(12) >> 8) | hibit)) as u64;
+
+        // acc *= r (mod 2^130 - 5)
+        let d0 = h0*r0 + h1*s4 + h2*s3 + h3*s2 + h4*s1;
+        let mut d1 = h0*r1 + h1*r0 + h2*s4 + h3*s3 + h4*s2;
+        let mut d2 = h0*r2 + h1*r1 + h2*r0 + h3*s4 + h4*s3;
+        let mut d3 = h0*r3 + h1*r2 + h2*r1 + h3*r0 + h4*s4;
+        let mut d4 = h0*r4 + h1*r3 + h2*r2 + h3*r1 + h4*r0;
+
+        // Partial carry propagation
+        let mut c: u64;
+        c = d0 >> 26; let mut h0 = d0 & 0x3ffffff;
+        d1 += c; c = d1 >> 26; let h1 = d1 & 0x3ffffff;
+        d2 += c; c = d2 >> 26; let h2 = d2 & 0x3ffffff;
+        d3 += c; c = d3 >> 26; let h3 = d3 & 0x3ffffff;
+        d4 += c; c = d4 >> 26; let h4 = d4 & 0x3ffffff;
+        h0 += c * 5; c = h0 >> 26; h0 &= 0x3ffffff;
+        let h1 = h1 + c;
+
+        self.acc = [h0 as u32, h1 as u32, h2 as u32, h3 as u32, h4 as u32];
+    }
+
+    fn finalize(&mut self) -> Vec<u8> {
+        if self.pending > 0 {
+            // Last block is padded with a one byte and no high bit
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..self.pending].copy_from_slice(&self.buffer[..self.pending]);
+            block[self.pending] = 1;
+            self.process_block(&block, 0);
+        }
+
+        // Full carry propagation
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.acc;
+        let mut c: u32;
+        c = h1 >> 26; h1 &= 0x3ffffff;
+        h2 += c; c = h2 >> 26; h2 &= 0x3ffffff;
+        h3 += c; c = h3 >> 26; h3 &= 0x3ffffff;
+        h4 += c; c = h4 >> 26; h4 &= 0x3ffffff;
+        h0 += c * 5; c = h0 >> 26; h0 &= 0x3ffffff;
+        h1 += c;
+
+        // g = h + 5 - 2^130, select h if g is negative
+        let mut g0 = h0.wrapping_add(5); c = g0 >> 26; g0 &= 0x3ffffff;
+        let mut g1 = h1.wrapping_add(c); c = g1 >> 26; g1 &= 0x3ffffff;
+        let mut g2 = h2.wrapping_add(c); c = g2 >> 26; g2 &= 0x3ffffff;
+        let mut g3 = h3.wrapping_add(c); c = g3 >> 26; g3 &= 0x3ffffff;
+        let g4 = h4.wrapping_add(c).wrapping_sub(1 << 26);
+
+        let mask = (g4 >> 31).wrapping_sub(1); // All ones if g is positive
+        h0 = (h0 & !mask) | (g0 & mask);
+        h1 = (h1 & !mask) | (g1 & mask);
+        h2 = (h2 & !mask) | (g2 & mask);
+        h3 = (h3 & !mask) | (g3 & mask);
+        h4 = (h4 & !mask) | (g4 & mask);
+
+        // h = h % 2^128, tag = h + s
+        let words = [
+            h0 | (h1 << 26),
+            (h1 >> 6) | (h2 << 20),
+            (h2 >> 12) | (h3 << 14),
+            (h3 >> 18) | (h4 << 8),
+        ];
+        let mut tag = Vec::with_capacity(TAG_SIZE);
+        let mut carry: u64 = 0;
+        for (word, s) in words.iter().zip(self.s) {
+            let sum = *word as u64 + s as u64 + carry;
+            tag.extend_from_slice(&(sum as u32).to_le_bytes());
+            carry = sum >> 32;
+        }
+        tag
+    }
+}
+
+impl Mac for Poly1305 {
+    fn new(key: &[u8]) -> Result<Self, InvalidKeyLen> {
+        if key.len() != KEY_SIZE {
+            return Err(InvalidKeyLen);
+        }
+        let t = |i: usize| u32::from_le_bytes(key[i..i+4].try_into().unwrap());
+
+        // r is clamped
+        let r = [
+            t(0) & 0x3ffffff,
+            (t(3) >> 2) & 0x3ffff03,
+            (t(6) >> 4) & 0x3ffc0ff,
+            (t(9) >> 6) & 0x3f03fff,
+            (t(12) >> 8) & 0x00fffff,
+        ];
+        let s = [t(16), t(20), t(24), t(28)];
+
+        Ok(Self {r, s, acc: [0; 5], buffer: [0; BLOCK_SIZE], pending: 0})
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let missing = BLOCK_SIZE - self.pending;
+            let (head, tail) = data.split_at(missing.min(data.len()));
+            self.buffer[self.pending..self.pending+head.len()]
+                .copy_from_slice(head);
+            self.pending += head.len();
+            data = tail;
+
+            if self.pending == BLOCK_SIZE {
+                let block = self.buffer;
+                self.process_block(&block, 1 << 24);
+                self.pending = 0;
+            }
+        }
+    }
+
+    fn generate(&mut self) -> Vec<u8> {
+        let tag = self.finalize();
+        self.reset();
+        tag
+    }
+
+    fn reset(&mut self) {
+        self.acc = [0; 5];
+        self.pending = 0;
+    }
+
+    fn size(&self) -> usize {
+        TAG_SIZE
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{Poly1305,Mac};
+    use crate::utils::decode_hex;
+
+    #[test]
+    fn poly1305() {
+        // RFC 8439 2.5.2
+        let key = decode_hex("85d6be7857556d337f4452fe42d506a80103808afb0db2fd\
+                              4abff6af4149f51b").unwrap();
+        let msg = "Cryptographic Forum Research Group".as_bytes();
+        let expected = decode_hex("a8061dc1305136c6c22b8baf0c0127a9").unwrap();
+        let mut poly = Poly1305::new(&key).expect("Invalid key");
+        poly.update(msg);
+        assert_eq!(poly.generate(), expected);
+        for chunk in msg.chunks(7) {
+            poly.update(chunk);
+        }
+        assert_eq!(poly.generate(), expected);
+    }
+
+    fn test_poly1305(key: &[u8], msg: &[u8], expected: &str) {
+        let mut poly = Poly1305::new(key).expect("Invalid key");
+        poly.update(msg);
+        assert_eq!(poly.generate(), decode_hex(expected).unwrap());
+    }
+
+    #[test]
+    fn poly1305_reduction() {
+        // RFC 8439 A.3 test vectors 6, 7, 8, edge cases of the reduction
+        let mut key = [0u8; 32];
+        key[0] = 2;
+        test_poly1305(&key, &[0xff; 16], "03000000000000000000000000000000");
+        key[16..].fill(0xff);
+        let mut msg = [0u8; 16];
+        msg[0] = 2;
+        test_poly1305(&key, &msg, "03000000000000000000000000000000");
+
+        let mut key = [0u8; 32];
+        key[0] = 1;
+        let mut msg = [0xff; 48];
+        msg[16] = 0xf0;
+        msg[32..].fill(0);
+        msg[32] = 0x11;
+        test_poly1305(&key, &msg, "05000000000000000000000000000000");
+        let mut msg = [0xfe; 48];
+        msg[..16].fill(0xff);
+        msg[16] = 0xfb;
+        msg[32..].fill(0x01);
+        test_poly1305(&key, &msg, "00000000000000000000000000000000");
+    }
+}
@@ -1,11 +0,0 @@
-mod cipher;
-mod error;
-mod hash;
-mod mac;
-mod modes;
-mod utils;
-
-
-fn main() {
-    println!("Run the tests!!!");
-}
@@ -1,14 +1,38 @@
-mod pkcs7_padding;
+pub mod padding;
 pub mod ecb;
 pub mod cbc;
+pub mod cbc_cs;
 pub mod ctr;
+pub mod cfb;
+pub mod ofb;
+pub mod xts;
+pub mod keywrap;
+pub mod gcm;
+pub mod ccm;
+pub mod siv;
+pub mod gcm_siv;
+pub mod ocb;
+pub mod eax;
+pub mod chacha20_poly1305;
+
+
+// Number of blocks handed to the cipher at once by the modes that can
+// process several blocks in parallel
+const PARALLEL_BLOCKS: usize = 8;
 
 
 #[cfg(test)]
 mod tests {
-    use crate::cipher::{Cipher,Aes128Cipher};
-    use crate::modes::{ecb, cbc, ctr};
+    use crate::cipher::{Cipher,Aes128Cipher,Aes192Cipher,Aes256Cipher,
+                        AesBackend,TripleDesCipher,
+                        ChaCha20,XChaCha20};
+    use crate::error::{AeadError,KeyError,KeyWrapError,ModeError};
+    use crate::modes::{ecb, cbc, cbc_cs, ctr, cfb, ofb, xts, keywrap, gcm,
+                       ccm, siv, gcm_siv, ocb, eax, chacha20_poly1305};
+    use crate::modes::padding::{Padding,Pkcs7,AnsiX923,Iso10126,Iso7816,
+                                ZeroPadding,NoPadding};
     use crate::utils::decode_hex;
+    use super::alloc_counter;
 
     const KEY: &[u8] = "yellow submarine".as_bytes();
     const MSG: &[u8] = "Hey friends, this is a longer message!!!".as_bytes();
@@ -38,6 +62,120 @@ mod tests {
         assert_eq!(decrypted, MSG);
     }
 
+    fn test_padding<P: Padding>(data: &[u8], expected: &[u8]) {
+        let padded = P::pad(data, 8).unwrap();
+        assert_eq!(padded[..expected.len()], *expected);
+        assert_eq!(P::unpad(&padded, 8).unwrap(), data);
+    }
+
+    #[test]
+    fn padding_schemes() {
+        let data = [0xdd; 5];
+        test_padding::<Pkcs7>(&data, &[0xdd, 0xdd, 0xdd, 0xdd, 0xdd, 3, 3, 3]);
+        test_padding::<AnsiX923>(&data, &[0xdd, 0xdd, 0xdd, 0xdd, 0xdd,
+                                          0, 0, 3]);
+        test_padding::<Iso7816>(&data, &[0xdd, 0xdd, 0xdd, 0xdd, 0xdd,
+                                         0x80, 0, 0]);
+        test_padding::<ZeroPadding>(&data, &[0xdd, 0xdd, 0xdd, 0xdd, 0xdd,
+                                             0, 0, 0]);
+        test_padding::<Iso10126>(&data, &data);
+        assert_eq!(Iso10126::pad(&data, 8).unwrap()[7], 3);
+
+        // Full padding block when data is block aligned
+        test_padding::<Pkcs7>(&[0xdd; 8], &[0xdd; 8]);
+        assert_eq!(Pkcs7::pad(&[0xdd; 8], 8).unwrap()[8..], [8; 8]);
+        assert_eq!(Iso7816::pad(&[], 8).unwrap(), [0x80, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ZeroPadding::pad(&[0xdd; 8], 8).unwrap(), [0xdd; 8]);
+        test_padding::<NoPadding>(&[0xdd; 16], &[0xdd; 16]);
+        assert!(NoPadding::pad(&data, 8).is_err());
+    }
+
+    #[test]
+    fn padding_invalid() {
+        assert!(Pkcs7::unpad(&[], 8).is_err());
+        assert!(Pkcs7::unpad(&[1, 2, 3, 4, 5, 6, 7, 0], 8).is_err());
+        assert!(Pkcs7::unpad(&[1, 2, 3, 4, 5, 6, 7, 9], 8).is_err());
+        assert!(Pkcs7::unpad(&[1, 2, 3, 4, 5, 6, 2, 3], 8).is_err());
+        assert!(AnsiX923::unpad(&[1, 2, 3, 4, 5, 6, 7, 3], 8).is_err());
+        assert!(Iso10126::unpad(&[1, 2, 3, 4, 5, 6, 7, 9], 8).is_err());
+        assert!(Iso7816::unpad(&[1, 2, 3, 4, 5, 6, 7, 0], 8).is_err());
+        assert!(Iso7816::unpad(&[0x80, 0, 0, 0, 0, 0, 0, 0, 0], 8).is_err());
+    }
+
+    #[test]
+    fn mode_cbc_padding() {
+        let aes = Aes128Cipher::new(KEY).expect("Key size error");
+        let iv: &[u8] = [0; 16].as_slice();
+        let encrypted = cbc::encrypt_padded::<Iso7816>(MSG, &aes, iv).unwrap();
+        assert_eq!(encrypted.len(), 48);
+        let decrypted = cbc::decrypt_padded::<Iso7816>(&encrypted, &aes, iv)
+            .unwrap();
+        assert_eq!(decrypted, MSG);
+
+        let result = cbc::encrypt_padded::<NoPadding>(MSG, &aes, iv);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+        let result = ecb::decrypt(&encrypted[..40], &aes);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+
+        // Wrong key is detected through the padding instead of returning
+        // garbage
+        let other_aes = Aes128Cipher::new(&[0; 16]).expect("Key size error");
+        let encrypted = cbc::encrypt(MSG, &aes, iv).unwrap();
+        let result = cbc::decrypt(&encrypted, &other_aes, iv);
+        assert!(matches!(result, Err(ModeError::InvalidPadding(_))));
+    }
+
+    #[test]
+    fn mode_cbc_cs() {
+        // RFC 3962 appendix B (CS3), the other variants only reorder blocks
+        let key = "chicken teriyaki".as_bytes();
+        let msg = decode_hex("4920776f756c64206c696b65207468652047656e657261\
+                              6c20476175277320436869636b656e2c20706c65617365\
+                              2c").unwrap();
+        let aes = Aes128Cipher::new(key).expect("Key size error");
+        let iv: &[u8] = [0; 16].as_slice();
+        let expected = [
+            (17, "c6353568f2bf8cb4d8a580362da7ff7f97"),
+            (31, "fc00783e0efdb2c1d445d4c8eff7ed2297687268d6ecccc0c07b25e25e\
+                  cfe5"),
+            (32, "39312523a78662d5be7fcbcc98ebf5a897687268d6ecccc0c07b25e25e\
+                  cfe584"),
+            (47, "97687268d6ecccc0c07b25e25ecfe584b3fffd940c16a18c1b5549d2f8\
+                  38029e39312523a78662d5be7fcbcc98ebf5"),
+        ];
+        for (len, expected) in expected {
+            let expected = decode_hex(expected).unwrap();
+            let encrypted = cbc_cs::encrypt(&msg[..len], &aes, iv,
+                                            cbc_cs::Variant::Cs3).unwrap();
+            assert_eq!(encrypted, expected);
+            let decrypted = cbc_cs::decrypt(&encrypted, &aes, iv,
+                                            cbc_cs::Variant::Cs3).unwrap();
+            assert_eq!(decrypted, msg[..len]);
+        }
+
+        let cs1 = cbc_cs::encrypt(&msg[..17], &aes, iv, cbc_cs::Variant::Cs1)
+            .unwrap();
+        assert_eq!(cs1, decode_hex("97c6353568f2bf8cb4d8a580362da7ff7f")
+                        .unwrap());
+        let cs2 = cbc_cs::encrypt(&msg[..32], &aes, iv, cbc_cs::Variant::Cs2)
+            .unwrap();
+        assert_eq!(cs2, cbc::encrypt_padded::<NoPadding>(&msg[..32], &aes, iv)
+                        .unwrap());
+        for variant in [cbc_cs::Variant::Cs1, cbc_cs::Variant::Cs2] {
+            for len in [16, 17, 31, 32, 47] {
+                let encrypted = cbc_cs::encrypt(&msg[..len], &aes, iv, variant)
+                    .unwrap();
+                assert_eq!(encrypted.len(), len);
+                let decrypted = cbc_cs::decrypt(&encrypted, &aes, iv, variant)
+                    .unwrap();
+                assert_eq!(decrypted, msg[..len]);
+            }
+        }
+        let result = cbc_cs::encrypt(&msg[..15], &aes, iv,
+                                     cbc_cs::Variant::Cs3);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+    }
+
     #[test]
     fn mode_ctr() {
         let expected = decode_hex("6c192f33797d95add6c89e26548e1c661bdd2941e5f\
@@ -50,4 +188,781 @@ mod tests {
         let decrypted = ctr::decrypt(&encrypted, &aes, nonce).unwrap();
         assert_eq!(decrypted, MSG);
     }
+
+    #[test]
+    fn mode_cbc_aes_256() {
+        // NIST SP 800-38A F.2.5, first block
+        let key = decode_hex("603deb1015ca71be2b73aef0857d77811f352c073b6108d7\
+                              2d9810a30914dff4").unwrap();
+        let iv = decode_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let msg = decode_hex("6bc1bee22e409f96e93d7e117393172a").unwrap();
+        let expected = decode_hex("f58c4c04d6e5f1ba779eabfb5f7bfbd6").unwrap();
+        let aes = Aes256Cipher::new(&key).expect("Key size error");
+        let encrypted = cbc::encrypt(&msg, &aes, &iv).unwrap();
+        assert_eq!(encrypted[..16], expected);
+        let decrypted = cbc::decrypt(&encrypted, &aes, &iv).unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    // NIST SP 800-38A appendix F
+    const SP_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const SP_IV: &str = "000102030405060708090a0b0c0d0e0f";
+    const SP_MSG: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c\
+                          9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52ef\
+                          f69f2445df4f9b17ad2b417be66c3710";
+
+    type ModeFn = fn(&[u8], &Aes128Cipher, &[u8])
+        -> Result<Vec<u8>, crate::error::InvalidIvLen>;
+
+    fn test_sp_mode(encrypt: ModeFn, decrypt: ModeFn, msg: &str,
+                    expected: &str) {
+        let aes = Aes128Cipher::new(&decode_hex(SP_KEY).unwrap()).unwrap();
+        let iv = decode_hex(SP_IV).unwrap();
+        let msg = decode_hex(msg).unwrap();
+        let expected = decode_hex(expected).unwrap();
+        let encrypted = encrypt(&msg, &aes, &iv).unwrap();
+        assert_eq!(encrypted, expected);
+        let decrypted = decrypt(&encrypted, &aes, &iv).unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_cfb_1() {
+        test_sp_mode(cfb::encrypt_1, cfb::decrypt_1, &SP_MSG[..4], "68b3");
+    }
+
+    #[test]
+    fn mode_cfb_8() {
+        test_sp_mode(cfb::encrypt_8, cfb::decrypt_8, &SP_MSG[..36],
+                     "3b79424c9c0dd436bace9e0ed4586a4f32b9");
+    }
+
+    #[test]
+    fn mode_cfb_128() {
+        test_sp_mode(cfb::encrypt, cfb::decrypt, SP_MSG,
+                     "3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3\
+                      cdad9f1ce58b26751f67a3cbb140b1808cf187a4f4dfc04b0535\
+                      7c5d1c0eeac4c66f9ff7f2e6");
+        test_sp_mode(cfb::encrypt, cfb::decrypt, &SP_MSG[..40],
+                     "3b3fd92eb72dad20333449f8e83cfb4ac8a64537");
+    }
+
+    #[test]
+    fn mode_cfb_64() {
+        let key = decode_hex("0123456789abcdef23456789abcdef01456789abcdef0123")
+            .unwrap();
+        let tdes = TripleDesCipher::new(&key).expect("Key size error");
+        let iv = decode_hex("0001020304050607").unwrap();
+        let msg = "The quick brown fox jumps".as_bytes();
+        let expected = decode_hex("645af773cc5c0c237f46ac00e5e73e01c87e475f66\
+                                   95526656").unwrap();
+        let encrypted = cfb::encrypt(msg, &tdes, &iv).unwrap();
+        assert_eq!(encrypted, expected);
+        let decrypted = cfb::decrypt(&encrypted, &tdes, &iv).unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_ofb() {
+        test_sp_mode(ofb::encrypt, ofb::decrypt, SP_MSG,
+                     "3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c\
+                      52dac54ed8259740051e9c5fecf64344f7a82260edcc304c6528\
+                      f659c77866a510d9c1d6ae5e");
+    }
+
+    #[test]
+    fn mode_ctr_multiple_groups() {
+        // Longer than the blocks processed at once by the cipher
+        let aes = Aes128Cipher::new(KEY).unwrap();
+        let msg: Vec<u8> = (0..200).collect();
+        let encrypted = ctr::encrypt(&msg, &aes, &[0; 8]).unwrap();
+        assert_eq!(encrypted[128..144],
+                   decode_hex("14d308b0c28f453a581abf790dd133fe").unwrap());
+        assert_eq!(encrypted[184..],
+                   decode_hex("a863285c89c451561983219bba0e0702").unwrap());
+    }
+
+    #[test]
+    fn mode_aes_backends() {
+        // Multiple groups of parallel blocks and a partial one
+        let msg: Vec<u8> = (0..=255).cycle().take(16 * 27).collect();
+        let reference = Aes256Cipher::with_backend(&[0x42; 32],
+                                                   AesBackend::Reference)
+            .unwrap();
+        let ecb_expected = ecb::encrypt(&msg, &reference).unwrap();
+        let ctr_expected = ctr::encrypt(&msg[5..], &reference, &[7; 8])
+            .unwrap();
+
+        for backend in [AesBackend::TTable, AesBackend::Bitsliced,
+                        AesBackend::AesNi] {
+            let aes = Aes256Cipher::with_backend(&[0x42; 32], backend)
+                .unwrap();
+            let encrypted = ecb::encrypt(&msg, &aes).unwrap();
+            assert_eq!(encrypted, ecb_expected);
+            assert_eq!(ecb::decrypt(&encrypted, &aes).unwrap(), msg);
+            let encrypted = ctr::encrypt(&msg[5..], &aes, &[7; 8]).unwrap();
+            assert_eq!(encrypted, ctr_expected);
+        }
+    }
+
+    #[test]
+    fn mode_in_place() {
+        let aes = Aes128Cipher::new(KEY).unwrap();
+        let iv = [0x24; 16];
+        let msg: Vec<u8> = (0..160).collect();
+
+        let mut data = msg.clone();
+        ecb::encrypt_in_place(&mut data, &aes).unwrap();
+        assert_eq!(data, ecb::encrypt_padded::<NoPadding>(&msg, &aes).unwrap());
+        ecb::decrypt_in_place(&mut data, &aes).unwrap();
+        assert_eq!(data, msg);
+
+        cbc::encrypt_in_place(&mut data, &aes, &iv).unwrap();
+        assert_eq!(data,
+                   cbc::encrypt_padded::<NoPadding>(&msg, &aes, &iv).unwrap());
+        cbc::decrypt_in_place(&mut data, &aes, &iv).unwrap();
+        assert_eq!(data, msg);
+
+        ctr::apply_keystream(&mut data, &aes, &iv[..8]).unwrap();
+        assert_eq!(data, ctr::encrypt(&msg, &aes, &iv[..8]).unwrap());
+        ctr::apply_keystream(&mut data, &aes, &iv[..8]).unwrap();
+        assert_eq!(data, msg);
+
+        ofb::apply_keystream(&mut data, &aes, &iv).unwrap();
+        assert_eq!(data, ofb::encrypt(&msg, &aes, &iv).unwrap());
+
+        let result = ecb::encrypt_in_place(&mut data[..20], &aes);
+        assert!(result.is_err());
+        let result = cbc::encrypt_in_place(&mut data[..20], &aes, &iv);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+    }
+
+    #[test]
+    fn mode_in_place_no_alloc() {
+        let aes = Aes128Cipher::new(KEY).unwrap();
+        let iv = [0x24; 16];
+        let mut data = vec![0x42; 4096];
+
+        let allocations = alloc_counter::count();
+        ecb::encrypt_in_place(&mut data, &aes).unwrap();
+        cbc::encrypt_in_place(&mut data, &aes, &iv).unwrap();
+        cbc::decrypt_in_place(&mut data, &aes, &iv).unwrap();
+        ctr::apply_keystream(&mut data, &aes, &iv[..8]).unwrap();
+        ofb::apply_keystream(&mut data, &aes, &iv).unwrap();
+        assert_eq!(alloc_counter::count(), allocations);
+    }
+
+    fn test_xts<C: Cipher<Block = [u8; 16]>>(key: &str, sector: u128,
+                                             msg: &[u8], expected: &str) {
+        let xts = xts::Xts::<C>::new(&decode_hex(key).unwrap()).unwrap();
+        let encrypted = xts.encrypt_sector(msg, sector).unwrap();
+        assert_eq!(encrypted, decode_hex(expected).unwrap());
+        let decrypted = xts.decrypt_sector(&encrypted, sector).unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_xts() {
+        // IEEE 1619 test vectors
+        test_xts::<Aes128Cipher>("1111111111111111111111111111111122222222222\
+                                  222222222222222222222", 0x3333333333,
+                                 &[0x44; 32],
+                                 "c454185e6a16936e39334038acef838bfb186fff7480\
+                                  adc4289382ecd6d394f0");
+        let msg: Vec<u8> = (0..=255).chain(0..=255).collect();
+        let xts = xts::Xts::<Aes128Cipher>::new(
+            &decode_hex("27182818284590452353602874713526314159265358979323\
+                         84626433832795").unwrap()).unwrap();
+        let encrypted = xts.encrypt_sector(&msg, 0).unwrap();
+        assert_eq!(encrypted[..32],
+                   decode_hex("27a7479befa1d476489f308cd4cfa6e2a96e4bbe3208ff\
+                               25287dd3819616e89c").unwrap());
+        assert_eq!(encrypted[480..],
+                   decode_hex("eb4a427d1923ce3ff262735779a418f20a282df920147b\
+                               eabe421ee5319d0568").unwrap());
+        assert_eq!(xts.decrypt_sector(&encrypted, 0).unwrap(), msg);
+    }
+
+    #[test]
+    fn mode_xts_stealing() {
+        let key = "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2\
+                   b1b0";
+        let msg: Vec<u8> = (0..20).collect();
+        test_xts::<Aes128Cipher>(key, 0x9a78563412, &msg[..17],
+                                 "641610679dcbf92e505c41333fb06c2a95");
+        test_xts::<Aes128Cipher>(key, 0x9a78563412, &msg,
+                                 "a8ba0048d75084603eb8423a09b7bf7595c871f6");
+    }
+
+    #[test]
+    fn mode_xts_aes_256() {
+        let key = decode_hex("2718281828459045235360287471352662497757247093699\
+                              9595749669676273141592653589793238462643383279502\
+                              884197169399375105820974944592").unwrap();
+        let msg: Vec<u8> = (0..=255).chain(0..=255).collect();
+        let xts = xts::Xts::<Aes256Cipher>::new(&key).unwrap();
+        let encrypted = xts.encrypt_sector(&msg, 0xff).unwrap();
+        assert_eq!(encrypted[..32],
+                   decode_hex("1c3b3a102f770386e4836c99e370cf9bea00803f5e4823\
+                               57a4ae12d414a3e63b").unwrap());
+        assert_eq!(xts.decrypt_sector(&encrypted, 0xff).unwrap(), msg);
+    }
+
+    #[test]
+    fn mode_xts_invalid() {
+        let result = xts::Xts::<Aes128Cipher>::new(&[0x11; 32]);
+        assert!(matches!(result, Err(KeyError::WeakKey(_))));
+        let result = xts::Xts::<Aes128Cipher>::new(&[0x11; 33]);
+        assert!(matches!(result, Err(KeyError::InvalidKeyLen(_))));
+
+        let mut key = [0x11; 32];
+        key[16..].fill(0x22);
+        let xts = xts::Xts::<Aes128Cipher>::new(&key).unwrap();
+        let result = xts.encrypt_sector(&[0; 15], 0);
+        assert!(matches!(result, Err(ModeError::InvalidDataLen(_))));
+        let result = xts.encrypt(&[0; 32], &[0; 8]);
+        assert!(matches!(result, Err(ModeError::InvalidIvLen(_))));
+    }
+
+    #[test]
+    fn mode_keywrap() {
+        // RFC 3394 test vectors
+        let kek = decode_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let cipher = Aes128Cipher::new(&kek).unwrap();
+        let key = decode_hex("00112233445566778899aabbccddeeff").unwrap();
+        let wrapped = keywrap::wrap(&key, &cipher).unwrap();
+        assert_eq!(wrapped,
+                   decode_hex("1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cf\
+                               e5").unwrap());
+        assert_eq!(keywrap::unwrap(&wrapped, &cipher).unwrap(), key);
+
+        let kek = decode_hex("000102030405060708090a0b0c0d0e0f101112131415161\
+                              718191a1b1c1d1e1f").unwrap();
+        let cipher = Aes256Cipher::new(&kek).unwrap();
+        let key = decode_hex("00112233445566778899aabbccddeeff000102030405060\
+                              708090a0b0c0d0e0f").unwrap();
+        let wrapped = keywrap::wrap(&key, &cipher).unwrap();
+        assert_eq!(wrapped,
+                   decode_hex("28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed3\
+                               26cbc7f0e71a99f43bfb988b9b7a02dd21").unwrap());
+        assert_eq!(keywrap::unwrap(&wrapped, &cipher).unwrap(), key);
+    }
+
+    #[test]
+    fn mode_keywrap_padded() {
+        // RFC 5649 test vectors
+        let kek = decode_hex("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a\
+                              8").unwrap();
+        let cipher = Aes192Cipher::new(&kek).unwrap();
+        let key = decode_hex("c37b7e6492584340bed12207808941155068f738")
+            .unwrap();
+        let wrapped = keywrap::wrap_padded(&key, &cipher).unwrap();
+        assert_eq!(wrapped,
+                   decode_hex("138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae\
+                               6a5f54f373fa543b6a").unwrap());
+        assert_eq!(keywrap::unwrap_padded(&wrapped, &cipher).unwrap(), key);
+
+        let key = decode_hex("466f7250617369").unwrap();
+        let wrapped = keywrap::wrap_padded(&key, &cipher).unwrap();
+        assert_eq!(wrapped,
+                   decode_hex("afbeb0f07dfbf5419200f2ccb50bb24f").unwrap());
+        assert_eq!(keywrap::unwrap_padded(&wrapped, &cipher).unwrap(), key);
+    }
+
+    #[test]
+    fn mode_keywrap_invalid() {
+        let cipher = Aes128Cipher::new(KEY).unwrap();
+        let mut wrapped = keywrap::wrap(&[0x42; 24], &cipher).unwrap();
+        wrapped[10] ^= 1;
+        let result = keywrap::unwrap(&wrapped, &cipher);
+        assert!(matches!(result, Err(KeyWrapError::IntegrityCheckFailed(_))));
+
+        let mut wrapped = keywrap::wrap_padded(&[0x42; 5], &cipher).unwrap();
+        wrapped[0] ^= 1;
+        let result = keywrap::unwrap_padded(&wrapped, &cipher);
+        assert!(matches!(result, Err(KeyWrapError::IntegrityCheckFailed(_))));
+
+        let result = keywrap::wrap(&[0x42; 20], &cipher);
+        assert!(matches!(result, Err(KeyWrapError::InvalidDataLen(_))));
+        let result = keywrap::wrap_padded(&[], &cipher);
+        assert!(matches!(result, Err(KeyWrapError::InvalidDataLen(_))));
+    }
+
+    // GCM specification test cases
+    const GCM_KEY: &str = "feffe9928665731c6d6a8f9467308308";
+    const GCM_MSG: &str = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da\
+                           2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525\
+                           b16aedf5aa0de657ba637b39";
+    const GCM_AAD: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+    fn test_gcm(key: &str, iv: &str, msg: &str, aad: &str, expected: &str,
+                expected_tag: &str) {
+        let aes = Aes128Cipher::new(&decode_hex(key).unwrap()).unwrap();
+        let iv = decode_hex(iv).unwrap();
+        let msg = decode_hex(msg).unwrap();
+        let aad = decode_hex(aad).unwrap();
+        let expected = decode_hex(expected).unwrap();
+        let expected_tag = decode_hex(expected_tag).unwrap();
+
+        let (encrypted, tag) = gcm::encrypt(&msg, &aes, &iv, &aad, 16).unwrap();
+        assert_eq!(encrypted, expected);
+        assert_eq!(tag, expected_tag);
+        let decrypted = gcm::decrypt(&encrypted, &aes, &iv, &aad, &tag)
+            .unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_gcm_zero() {
+        test_gcm("00000000000000000000000000000000", "000000000000000000000000",
+                 "", "", "", "58e2fccefa7e3061367f1d57a4e7455a");
+        test_gcm("00000000000000000000000000000000", "000000000000000000000000",
+                 "00000000000000000000000000000000", "",
+                 "0388dace60b6a392f328c2b971b2fe78",
+                 "ab6e47d42cec13bdf53a67b21257bddf");
+    }
+
+    #[test]
+    fn mode_gcm_aad() {
+        test_gcm(GCM_KEY, "cafebabefacedbaddecaf888", GCM_MSG, GCM_AAD,
+                 "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e23\
+                  29aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac97\
+                  3d58e091", "5bc94fbc3221a5db94fae95ae7121a47");
+    }
+
+    #[test]
+    fn mode_gcm_long_iv() {
+        test_gcm(GCM_KEY, "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e\
+                           4c303d2a318a728c3c0c95156809539fcf0e2429a6b525416\
+                           aedbf5a0de6a57a637b39b", GCM_MSG, GCM_AAD,
+                 "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3c\
+                  ca7e2ca701e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca41703\
+                  4c34aee5", "619cc5aefffe0bfa462af43c1699d050");
+    }
+
+    #[test]
+    fn mode_gcm_tag() {
+        let aes = Aes128Cipher::new(KEY).expect("Key size error");
+        let iv: &[u8] = [0; 12].as_slice();
+        let (encrypted, tag) = gcm::encrypt(MSG, &aes, iv, &[], 12).unwrap();
+        assert_eq!(tag.len(), 12);
+        assert!(gcm::decrypt(&encrypted, &aes, iv, &[], &tag).is_ok());
+
+        let mut bad_tag = tag.clone();
+        bad_tag[0] ^= 1;
+        let result = gcm::decrypt(&encrypted, &aes, iv, &[], &bad_tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = gcm::decrypt(&encrypted, &aes, iv, &[], &tag[..10]);
+        assert!(matches!(result, Err(AeadError::InvalidTagLen(_))));
+        let result = gcm::encrypt(MSG, &aes, &[], &[], 16);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+    }
+
+    const SUNSCREEN: &[u8] = "Ladies and Gentlemen of the class of '99: If I \
+                              could offer you only one tip for the future, \
+                              sunscreen would be it.".as_bytes();
+
+    fn test_ccm(nonce: &str, msg: &str, aad: &str, expected: &str,
+                expected_tag: &str) {
+        let aes = Aes128Cipher::new(&decode_hex(CCM_KEY).unwrap()).unwrap();
+        let nonce = decode_hex(nonce).unwrap();
+        let msg = decode_hex(msg).unwrap();
+        let aad = decode_hex(aad).unwrap();
+        let expected_tag = decode_hex(expected_tag).unwrap();
+
+        let (encrypted, tag) = ccm::encrypt(&msg, &aes, &nonce, &aad,
+                                            expected_tag.len()).unwrap();
+        assert_eq!(encrypted, decode_hex(expected).unwrap());
+        assert_eq!(tag, expected_tag);
+        let decrypted = ccm::decrypt(&encrypted, &aes, &nonce, &aad, &tag)
+            .unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    // SP 800-38C example vectors
+    const CCM_KEY: &str = "404142434445464748494a4b4c4d4e4f";
+
+    #[test]
+    fn mode_ccm() {
+        test_ccm("10111213141516", "20212223", "0001020304050607", "7162015b",
+                 "4dac255d");
+        test_ccm("1011121314151617", "202122232425262728292a2b2c2d2e2f",
+                 "000102030405060708090a0b0c0d0e0f",
+                 "d2a1f0e051ea5f62081a7792073d593d", "1fc64fbfaccd");
+        test_ccm("101112131415161718191a1b",
+                 "202122232425262728292a2b2c2d2e2f3031323334353637",
+                 "000102030405060708090a0b0c0d0e0f10111213",
+                 "e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428aa5",
+                 "484392fbc1b09951");
+    }
+
+    #[test]
+    fn mode_ccm_long_aad() {
+        let aes = Aes128Cipher::new(&decode_hex(CCM_KEY).unwrap()).unwrap();
+        let nonce = decode_hex("101112131415161718191a1b").unwrap();
+        let msg = decode_hex("202122232425262728292a2b2c2d2e2f3031323334353637")
+            .unwrap();
+        let aad: Vec<u8> = (0..=255).cycle().take(256 * 300).collect();
+        let (encrypted, tag) = ccm::encrypt(&msg, &aes, &nonce, &aad, 16)
+            .unwrap();
+        assert_eq!(tag,
+                   decode_hex("5c6d6e3f82fd06ffbe297b734180af7c").unwrap());
+        assert_eq!(ccm::decrypt(&encrypted, &aes, &nonce, &aad, &tag).unwrap(),
+                   msg);
+    }
+
+    #[test]
+    fn mode_ccm_star() {
+        let aes = Aes128Cipher::new(&decode_hex(CCM_KEY).unwrap()).unwrap();
+        let nonce = decode_hex("101112131415161718191a1b").unwrap();
+        let msg = decode_hex("202122232425262728292a2b2c2d2e2f3031323334353637")
+            .unwrap();
+        let aad = decode_hex("000102030405060708090a0b0c0d0e0f10111213")
+            .unwrap();
+
+        // Without a tag CCM* is plain CTR encryption
+        let (encrypted, tag) = ccm::encrypt_star(&msg, &aes, &nonce, &aad, 0)
+            .unwrap();
+        assert_eq!(encrypted,
+                   decode_hex("e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428a\
+                               a5").unwrap());
+        assert!(tag.is_empty());
+        assert_eq!(ccm::decrypt_star(&encrypted, &aes, &nonce, &aad, &tag)
+                       .unwrap(), msg);
+
+        let (encrypted, tag) = ccm::encrypt_star(&msg, &aes, &nonce, &aad, 8)
+            .unwrap();
+        assert_eq!(tag, decode_hex("484392fbc1b09951").unwrap());
+        assert_eq!(ccm::decrypt_star(&encrypted, &aes, &nonce, &aad, &tag)
+                       .unwrap(), msg);
+    }
+
+    #[test]
+    fn mode_ccm_invalid() {
+        let aes = Aes128Cipher::new(KEY).unwrap();
+        let nonce = [0; 13];
+        let (encrypted, mut tag) = ccm::encrypt(MSG, &aes, &nonce, &[], 8)
+            .unwrap();
+        tag[0] ^= 1;
+        let result = ccm::decrypt(&encrypted, &aes, &nonce, &[], &tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+
+        let result = ccm::encrypt(MSG, &aes, &nonce, &[], 0);
+        assert!(matches!(result, Err(AeadError::InvalidTagLen(_))));
+        let result = ccm::encrypt(MSG, &aes, &nonce, &[], 5);
+        assert!(matches!(result, Err(AeadError::InvalidTagLen(_))));
+        let result = ccm::encrypt(MSG, &aes, &[0; 6], &[], 8);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+        let result = ccm::encrypt(&[0; 0x10000], &aes, &nonce, &[], 8);
+        assert!(matches!(result, Err(AeadError::InvalidDataLen(_))));
+    }
+
+    #[test]
+    fn mode_siv() {
+        // RFC 5297 test vectors
+        let mut siv = siv::Siv::<Aes128Cipher>::new(
+            &decode_hex("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0f0f1f2f3f4f5f6f7f8f9fa\
+                         fbfcfdfeff").unwrap()).unwrap();
+        let aad = decode_hex("101112131415161718191a1b1c1d1e1f2021222324252627")
+            .unwrap();
+        let msg = decode_hex("112233445566778899aabbccddee").unwrap();
+        let encrypted = siv.encrypt(&msg, &[&aad]).unwrap();
+        assert_eq!(encrypted,
+                   decode_hex("85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc\
+                               04daef7f6afe5c").unwrap());
+        assert_eq!(siv.decrypt(&encrypted, &[&aad]).unwrap(), msg);
+
+        let mut siv = siv::Siv::<Aes128Cipher>::new(
+            &decode_hex("7f7e7d7c7b7a79787776757473727170404142434445464748494a\
+                         4b4c4d4e4f").unwrap()).unwrap();
+        let aad1 = decode_hex("00112233445566778899aabbccddeeffdeaddadadeaddad\
+                               affeeddccbbaa99887766554433221100").unwrap();
+        let aad2 = decode_hex("102030405060708090a0").unwrap();
+        let nonce = decode_hex("09f911029d74e35bd84156c5635688c0").unwrap();
+        let msg = decode_hex("7468697320697320736f6d6520706c61696e74657874207\
+                              46f20656e6372797074207573696e67205349562d414553")
+            .unwrap();
+        let encrypted = siv.encrypt(&msg, &[&aad1, &aad2, &nonce]).unwrap();
+        assert_eq!(encrypted,
+                   decode_hex("7bdb6e3b432667eb06f4d14bff2fbd0fcb900f2fddbe40\
+                               4326601965c889bf17dba77ceb094fa663b7a3f748ba8a\
+                               f829ea64ad544a272e9c485b62a3fd5c0d").unwrap());
+        assert_eq!(siv.decrypt(&encrypted, &[&aad1, &aad2, &nonce]).unwrap(),
+                   msg);
+    }
+
+    #[test]
+    fn mode_siv_invalid() {
+        let key: Vec<u8> = (0..64).collect();
+        let mut siv = siv::Siv::<Aes256Cipher>::new(&key).unwrap();
+        let mut encrypted = siv.encrypt(b"hello world", &[]).unwrap();
+        assert_eq!(encrypted,
+                   decode_hex("83559c77cac73514701e107e4616f91e4c37f489a2285e\
+                               3bc09dd8").unwrap());
+        encrypted[20] ^= 1;
+        let result = siv.decrypt(&encrypted, &[]);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = siv.decrypt(&encrypted, &[b"aad"]);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = siv.decrypt(&encrypted[..15], &[]);
+        assert!(matches!(result, Err(AeadError::InvalidDataLen(_))));
+
+        let result = siv::Siv::<Aes128Cipher>::new(&key[..33]);
+        assert!(matches!(result, Err(KeyError::InvalidKeyLen(_))));
+    }
+
+    fn test_gcm_siv<C: Cipher<Block = [u8; 16]>>(key: &str, msg: &str,
+                                                 aad: &str, expected: &str) {
+        let gcm_siv = gcm_siv::GcmSiv::<C>::new(&decode_hex(key).unwrap())
+            .unwrap();
+        let nonce = decode_hex("030000000000000000000000").unwrap();
+        let msg = decode_hex(msg).unwrap();
+        let aad = decode_hex(aad).unwrap();
+        let expected = decode_hex(expected).unwrap();
+
+        let (encrypted, tag) = gcm_siv.encrypt(&msg, &nonce, &aad).unwrap();
+        assert_eq!([encrypted.clone(), tag.clone()].concat(), expected);
+        let decrypted = gcm_siv.decrypt(&encrypted, &nonce, &aad, &tag)
+            .unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_gcm_siv() {
+        // RFC 8452 test vectors
+        let key = "01000000000000000000000000000000";
+        test_gcm_siv::<Aes128Cipher>(key, "", "",
+                                     "dc20e2d83f25705bb49e439eca56de25");
+        test_gcm_siv::<Aes128Cipher>(key, "0100000000000000", "",
+                                     "b5d839330ac7b786578782fff6013b815b287c22\
+                                      493a364c");
+        test_gcm_siv::<Aes128Cipher>(key, "02000000000000000000000000000000\
+                                           03000000000000000000000000000000",
+                                     "01",
+                                     "620048ef3c1e73e57e02bb8562c416a319e73e4c\
+                                      aac8e96a1ecb2933145a1d71e6af6a7f87287da0\
+                                      59a71684ed3498e1");
+        test_gcm_siv::<Aes256Cipher>("010000000000000000000000000000000000000\
+                                      0000000000000000000000000",
+                                     "02000000000000000000000000000000\
+                                      03000000000000000000000000000000\
+                                      04000000",
+                                     "010000000000000000000000",
+                                     "97d8b33c677637fa88cb0e6839238473d34a1013\
+                                      3d9d169b137ce91d773771b1f3dd7d1d12b4452f\
+                                      2499650fcd485d7adef976ff");
+    }
+
+    #[test]
+    fn mode_gcm_siv_invalid() {
+        let gcm_siv = gcm_siv::GcmSiv::<Aes128Cipher>::new(KEY).unwrap();
+        let nonce = [0; 12];
+        let (encrypted, mut tag) = gcm_siv.encrypt(MSG, &nonce, &[]).unwrap();
+        tag[0] ^= 1;
+        let result = gcm_siv.decrypt(&encrypted, &nonce, &[], &tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = gcm_siv.encrypt(MSG, &[0; 16], &[]);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+        let result = gcm_siv::GcmSiv::<Aes192Cipher>::new(&[0; 24]);
+        assert!(matches!(result, Err(KeyError::InvalidKeyLen(_))));
+    }
+
+    fn test_ocb(key: &[u8], nonce: &str, msg: &[u8], aad: &[u8],
+                expected: &str) {
+        let aes = Aes128Cipher::new(key).unwrap();
+        let nonce = decode_hex(nonce).unwrap();
+        let expected = decode_hex(expected).unwrap();
+        let tag_len = expected.len() - msg.len();
+
+        let (encrypted, tag) = ocb::encrypt(msg, &aes, &nonce, aad, tag_len)
+            .unwrap();
+        assert_eq!([encrypted.clone(), tag.clone()].concat(), expected);
+        let decrypted = ocb::decrypt(&encrypted, &aes, &nonce, aad, &tag)
+            .unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_ocb() {
+        // RFC 7253 test vectors
+        let key: Vec<u8> = (0..16).collect();
+        let data: Vec<u8> = (0..40).collect();
+        test_ocb(&key, "bbaa99887766554433221100", &[], &[],
+                 "785407bfffc8ad9edcc5520ac9111ee6");
+        test_ocb(&key, "bbaa99887766554433221101", &data[..8], &data[..8],
+                 "6820b3657b6f615a5725bda0d3b4eb3a257c9af1f8f03009");
+        test_ocb(&key, "bbaa99887766554433221104", &data[..24], &data[..24],
+                 "571d535b60b277188be5147170a9a22c04f35a3f906b4b9c54e7aa90\
+                  c066ae33e48daffa6401845f");
+        test_ocb(&key, "bbaa99887766554433221107", &data, &data,
+                 "1ca2207308c87c010756104d8840ce196cda7324e9be130b74bf92bf\
+                  b8ae3210f1634662484b8d2bc7f9da5f69c42c8faf71980643ca0337");
+        test_ocb(&key, "bbaa9988776655443322110d", &data, &[],
+                 "d5ca91748410c1751ff8a2f618255b68a0a12e093ff454606e59f9c1\
+                  d0ddc54b65e8628e568bad7a8e975c5341d3d5d0cf3258cf7fee7f74");
+
+        // 96 bit tag
+        let key: Vec<u8> = (0..16).rev().collect();
+        test_ocb(&key, "bbaa9988776655443322110d", &data, &data,
+                 "1792a4e31e0755fb03e31b22116e6c2ddf9efd6e33d536f1a0124b0a\
+                  55bae884ed93481529c76b6ad0c515f4d1cdd4fdac4f02aa");
+    }
+
+    #[test]
+    fn mode_ocb_invalid() {
+        let aes = Aes128Cipher::new(KEY).unwrap();
+        let nonce = [0; 12];
+        let (encrypted, mut tag) = ocb::encrypt(MSG, &aes, &nonce, &[], 16)
+            .unwrap();
+        tag[0] ^= 1;
+        let result = ocb::decrypt(&encrypted, &aes, &nonce, &[], &tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = ocb::encrypt(MSG, &aes, &[0; 16], &[], 16);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+        let result = ocb::encrypt(MSG, &aes, &nonce, &[], 17);
+        assert!(matches!(result, Err(AeadError::InvalidTagLen(_))));
+    }
+
+    fn test_eax(key: &str, nonce: &str, msg: &str, aad: &str, expected: &str) {
+        let mut eax = eax::Eax::<Aes128Cipher>::new(&decode_hex(key).unwrap())
+            .unwrap();
+        let nonce = decode_hex(nonce).unwrap();
+        let msg = decode_hex(msg).unwrap();
+        let aad = decode_hex(aad).unwrap();
+        let expected = decode_hex(expected).unwrap();
+
+        let (encrypted, tag) = eax.encrypt(&msg, &nonce, &aad, 16).unwrap();
+        assert_eq!([encrypted.clone(), tag.clone()].concat(), expected);
+        let decrypted = eax.decrypt(&encrypted, &nonce, &aad, &tag).unwrap();
+        assert_eq!(decrypted, msg);
+    }
+
+    #[test]
+    fn mode_eax() {
+        // Test vectors from the EAX paper
+        test_eax("233952dee4d5ed5f9b9c6d6ff80ff478",
+                 "62ec67f9c3a4a407fcb2a8c49031a8b3", "", "6bfb914fd07eae6b",
+                 "e037830e8389f27b025a2d6527e79d01");
+        test_eax("91945d3f4dcbee0bf45ef52255f095a4",
+                 "becaf043b0a23d843194ba972c66debd", "f7fb", "fa3bfd4806eb53fa",
+                 "19dd5c4c9331049d0bdab0277408f67967e5");
+        test_eax("01f74ad64077f2e704c0f60ada3dd523",
+                 "70c3db4f0d26368400a10ed05d2bff5e", "1a47cb4933",
+                 "234a3463c1264ac6",
+                 "d851d5bae03a59f238a23e39199dc9266626c40f80");
+        test_eax("7c77d6e813bed5ac98baa417477a2e7d",
+                 "1a8c98dcd73d38393b2bf1569deefc19",
+                 "8b0a79306c9ce7ed99dae4f87f8dd61636", "65d2017990d62528",
+                 "02083e3979da014812f59f11d52630da30137327d10649b0aa6e1c181d\
+                  b617d7f2");
+    }
+
+    #[test]
+    fn mode_eax_invalid() {
+        let mut eax = eax::Eax::<Aes128Cipher>::new(KEY).unwrap();
+        let nonce = [0; 16];
+        let (mut encrypted, tag) = eax.encrypt(MSG, &nonce, b"aad", 8)
+            .unwrap();
+        assert_eq!(tag.len(), 8);
+        let result = eax.decrypt(&encrypted, &nonce, b"", &tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        encrypted[0] ^= 1;
+        let result = eax.decrypt(&encrypted, &nonce, b"aad", &tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = eax.encrypt(MSG, &nonce, b"aad", 0);
+        assert!(matches!(result, Err(AeadError::InvalidTagLen(_))));
+    }
+
+    #[test]
+    fn mode_chacha20_poly1305() {
+        // RFC 8439 2.8.2
+        let key: Vec<u8> = (0x80..0xa0).collect();
+        let nonce = decode_hex("070000004041424344454647").unwrap();
+        let aad = decode_hex("50515253c0c1c2c3c4c5c6c7").unwrap();
+        let expected = decode_hex("d31a8d34648e60db7b86afbc53ef7ec2a4aded5129\
+                                   6e08fea9e2b5a736ee62d63dbea45e8ca9671282fa\
+                                   fb69da92728b1a71de0a9e060b2905d6a5b67ecd3b\
+                                   3692ddbd7f2d778b8c9803aee328091b58fab324e4\
+                                   fad675945585808b4831d7bc3ff4def08e4b7a9de5\
+                                   76d26586cec64b6116").unwrap();
+        let expected_tag = decode_hex("1ae10b594f09e26a7e902ecbd0600691")
+            .unwrap();
+        let chacha = ChaCha20::new(&key).expect("Key size error");
+        let (encrypted, tag) = chacha20_poly1305::encrypt(SUNSCREEN, &chacha,
+                                                          &nonce, &aad)
+            .unwrap();
+        assert_eq!(encrypted, expected);
+        assert_eq!(tag, expected_tag);
+        let decrypted = chacha20_poly1305::decrypt(&encrypted, &chacha, &nonce,
+                                                   &aad, &tag).unwrap();
+        assert_eq!(decrypted, SUNSCREEN);
+
+        let result = chacha20_poly1305::decrypt(&encrypted, &chacha, &nonce,
+                                                &[], &tag);
+        assert!(matches!(result, Err(AeadError::AuthenticationFailed(_))));
+        let result = chacha20_poly1305::decrypt(&encrypted, &chacha, &nonce,
+                                                &aad, &tag[..12]);
+        assert!(matches!(result, Err(AeadError::InvalidTagLen(_))));
+    }
+
+    #[test]
+    fn mode_xchacha20_poly1305() {
+        // draft-irtf-cfrg-xchacha A.3.1
+        let key: Vec<u8> = (0x80..0xa0).collect();
+        let nonce: Vec<u8> = (0x40..0x58).collect();
+        let aad = decode_hex("50515253c0c1c2c3c4c5c6c7").unwrap();
+        let expected = decode_hex("bd6d179d3e83d43b9576579493c0e939572a170025\
+                                   2bfaccbed2902c21396cbb731c7f1b0b4aa6440bf3\
+                                   a82f4eda7e39ae64c6708c54c216cb96b72e1213b4\
+                                   522f8c9ba40db5d945b11b69b982c1bb9e3f3fac2b\
+                                   c369488f76b2383565d3fff921f9664c97637da976\
+                                   8812f615c68b13b52e").unwrap();
+        let expected_tag = decode_hex("c0875924c1c7987947deafd8780acf49")
+            .unwrap();
+        let xchacha = XChaCha20::new(&key).expect("Key size error");
+        let (encrypted, tag) = chacha20_poly1305::encrypt_x(SUNSCREEN, &xchacha,
+                                                            &nonce, &aad)
+            .unwrap();
+        assert_eq!(encrypted, expected);
+        assert_eq!(tag, expected_tag);
+        let decrypted = chacha20_poly1305::decrypt_x(&encrypted, &xchacha,
+                                                     &nonce, &aad, &tag)
+            .unwrap();
+        assert_eq!(decrypted, SUNSCREEN);
+        let result = chacha20_poly1305::encrypt_x(SUNSCREEN, &xchacha,
+                                                  &nonce[..12], &aad);
+        assert!(matches!(result, Err(AeadError::InvalidIvLen(_))));
+    }
+}
+
+
+// Counts the heap allocations made by the current thread
+#[cfg(test)]
+mod alloc_counter {
+    use std::alloc::{GlobalAlloc,Layout,System};
+    use std::cell::Cell;
+
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|n| n.set(n.get() + 1));
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    pub fn count() -> usize {
+        ALLOCATIONS.with(|n| n.get())
+    }
 }
@@ -1,46 +1,76 @@
-use crate::cipher::Cipher;
-use crate::error::InvalidIvLen;
+use crate::cipher::{BlockArray,Cipher};
+use crate::error::{InvalidDataLen,InvalidIvLen,ModeError};
 use crate::utils;
-use super::pkcs7_padding;
+use super::padding::{Padding,Pkcs7};
 
 
 pub fn encrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
-        -> Result<Vec<u8>,InvalidIvLen> {
-    let padded = pkcs7_padding::pad(data, cipher.block_size());
-    let mut encrypted = Vec::<u8>::with_capacity(padded.len());
+        -> Result<Vec<u8>,ModeError> {
+    encrypt_padded::<Pkcs7>(data, cipher, iv)
+}
+
+pub fn decrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,ModeError> {
+    decrypt_padded::<Pkcs7>(data, cipher, iv)
+}
 
+pub fn encrypt_padded<P: Padding>(data: &[u8], cipher: &impl Cipher,
+        iv: &[u8]) -> Result<Vec<u8>,ModeError> {
     if iv.len() != cipher.block_size() {
-        return Err(InvalidIvLen);
-    }
-    let mut prev_ciphertext: Vec<u8> = iv.to_owned();
-    for block in padded.chunks_exact(cipher.block_size()) {
-        utils::xor_slice(&mut prev_ciphertext, block);
-        let ciphertext = cipher.encrypt(&prev_ciphertext)
-            .expect("Invalid block size");
-        encrypted.extend_from_slice(ciphertext.as_slice());
-        prev_ciphertext.copy_from_slice(ciphertext.as_slice());
+        return Err(InvalidIvLen.into());
     }
-
+    let mut encrypted = P::pad(data, cipher.block_size())?;
+    encrypt_in_place(&mut encrypted, cipher, iv)?;
     Ok(encrypted)
 }
 
-pub fn decrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
-        -> Result<Vec<u8>,InvalidIvLen> {
-    let mut decrypted = Vec::<u8>::with_capacity(data.len());
+pub fn decrypt_padded<P: Padding>(data: &[u8], cipher: &impl Cipher,
+        iv: &[u8]) -> Result<Vec<u8>,ModeError> {
+    let mut decrypted = data.to_vec();
+    decrypt_in_place(&mut decrypted, cipher, iv)?;
 
-    if iv.len() != cipher.block_size() {
-        return Err(InvalidIvLen);
+    let unpadded = P::unpad(decrypted.as_slice(), cipher.block_size())?;
+    decrypted.truncate(unpadded.len());
+    Ok(decrypted)
+}
+
+// Unpadded variants, data must be a multiple of the block size
+pub fn encrypt_in_place<C: Cipher>(data: &mut [u8], cipher: &C, iv: &[u8])
+        -> Result<(),ModeError> {
+    let (blocks, iv) = split_blocks::<C>(data, iv)?;
+
+    let mut prev_ciphertext = iv;
+    for block in blocks {
+        utils::xor_slice(block.as_mut(), prev_ciphertext.as_ref());
+        cipher.encrypt_block(block);
+        prev_ciphertext = *block;
     }
-    let mut prev_ciphertext: Vec<u8> = iv.to_owned();
-    for block in data.chunks_exact(cipher.block_size()) {
-        let mut plaintext = cipher.decrypt(block)
-            .expect("Invalid block size");
-        utils::xor_slice(plaintext.as_mut_slice(), prev_ciphertext.as_slice());
-        decrypted.extend_from_slice(plaintext.as_slice());
-        prev_ciphertext.copy_from_slice(block);
+
+    Ok(())
+}
+
+pub fn decrypt_in_place<C: Cipher>(data: &mut [u8], cipher: &C, iv: &[u8])
+        -> Result<(),ModeError> {
+    let (blocks, iv) = split_blocks::<C>(data, iv)?;
+
+    // Blocks are decrypted from the end so the previous ciphertext block is
+    // still available
+    for i in (0..blocks.len()).rev() {
+        let prev_ciphertext = if i == 0 { iv } else { blocks[i-1] };
+        cipher.decrypt_block(&mut blocks[i]);
+        utils::xor_slice(blocks[i].as_mut(), prev_ciphertext.as_ref());
     }
 
-    let unpadded = pkcs7_padding::unpad(decrypted.as_slice());
-    decrypted.truncate(unpadded.len());
-    Ok(decrypted)
+    Ok(())
+}
+
+
+// Data viewed as blocks and the IV as a block
+fn split_blocks<'a, C: Cipher>(data: &'a mut [u8], iv: &[u8])
+        -> Result<(&'a mut [C::Block], C::Block),ModeError> {
+    let iv = C::to_block(iv).map_err(|_| InvalidIvLen)?;
+    let (blocks, []) = C::Block::split_mut(data) else {
+        return Err(InvalidDataLen.into());
+    };
+    Ok((blocks, iv))
 }
@@ -0,0 +1,103 @@
+use crate::cipher::Cipher;
+use crate::error::{InvalidDataLen,ModeError};
+use super::cbc;
+use super::padding::NoPadding;
+
+
+// Ciphertext stealing variants (SP 800-38A addendum), they differ in the
+// order of the last two ciphertext blocks
+#[derive(Clone, Copy, PartialEq)]
+pub enum Variant {
+    Cs1, // Partial block goes before the last full block
+    Cs2, // Blocks are swapped only if the last block is partial
+    Cs3, // Blocks are always swapped, as used by Kerberos
+}
+
+
+// Data must be at least one block long, output has the same length
+pub fn encrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8], variant: Variant)
+        -> Result<Vec<u8>,ModeError> {
+    let block_size = cipher.block_size();
+    if data.len() < block_size {
+        return Err(InvalidDataLen.into());
+    }
+    if data.len() == block_size {
+        return cbc::encrypt_padded::<NoPadding>(data, cipher, iv);
+    }
+    let d = last_block_size(data.len(), block_size);
+
+    let mut padded = data.to_vec();
+    padded.resize(data.len() + block_size - d, 0);
+    let mut encrypted = cbc::encrypt_padded::<NoPadding>(&padded, cipher, iv)?;
+
+    // Drop the stolen bytes of the penultimate block
+    let stolen_start = encrypted.len() - 2*block_size + d;
+    encrypted.drain(stolen_start..encrypted.len()-block_size);
+
+    swap_last_blocks(&mut encrypted, block_size, variant);
+    Ok(encrypted)
+}
+
+pub fn decrypt<C: Cipher>(data: &[u8], cipher: &C, iv: &[u8], variant: Variant)
+        -> Result<Vec<u8>,ModeError> {
+    let block_size = cipher.block_size();
+    if data.len() < block_size {
+        return Err(InvalidDataLen.into());
+    }
+    if data.len() == block_size {
+        return cbc::decrypt_padded::<NoPadding>(data, cipher, iv);
+    }
+    let d = last_block_size(data.len(), block_size);
+
+    // Bring the ciphertext back to the CS1 order
+    let mut ciphertext = data.to_vec();
+    unswap_last_blocks(&mut ciphertext, block_size, variant);
+
+    // Recover the stolen bytes from the decryption of the last block
+    let last_start = ciphertext.len() - block_size;
+    let mut z = C::to_block(&ciphertext[last_start..])?;
+    cipher.decrypt_block(&mut z);
+    ciphertext.truncate(last_start);
+    ciphertext.extend_from_slice(&z.as_ref()[d..]);
+
+    let mut decrypted = cbc::decrypt_padded::<NoPadding>(&ciphertext, cipher,
+                                                         iv)?;
+    let penultimate = &ciphertext[ciphertext.len()-block_size..];
+    for (z, c) in z.as_ref()[..d].iter().zip(penultimate) {
+        decrypted.push(z ^ c);
+    }
+    Ok(decrypted)
+}
+
+
+fn last_block_size(len: usize, block_size: usize) -> usize {
+    match len % block_size {
+        0 => block_size,
+        d => d,
+    }
+}
+
+fn swapped(len: usize, block_size: usize, variant: Variant) -> bool {
+    len > block_size && match variant {
+        Variant::Cs1 => false,
+        Variant::Cs2 => last_block_size(len, block_size) != block_size,
+        Variant::Cs3 => true,
+    }
+}
+
+// CS1 ends with C*n-1 || Cn, swapped variants end with Cn || C*n-1
+fn swap_last_blocks(data: &mut [u8], block_size: usize, variant: Variant) {
+    if swapped(data.len(), block_size, variant) {
+        let d = last_block_size(data.len(), block_size);
+        let tail_start = data.len() - block_size - d;
+        data[tail_start..].rotate_left(d);
+    }
+}
+
+fn unswap_last_blocks(data: &mut [u8], block_size: usize, variant: Variant) {
+    if swapped(data.len(), block_size, variant) {
+        let d = last_block_size(data.len(), block_size);
+        let tail_start = data.len() - block_size - d;
+        data[tail_start..].rotate_left(block_size);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::cipher::Cipher;
+use crate::error::{AeadError,AuthenticationFailed,InvalidDataLen,InvalidIvLen,
+                   InvalidTagLen};
+use crate::utils;
+use super::ctr;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 13; // Bluetooth LE and 802.15.4 nonce size
+
+// Allowed nonce and tag sizes (SP 800-38C A.1)
+const MIN_NONCE_SIZE: usize = 7;
+const MAX_NONCE_SIZE: usize = 13;
+const TAG_SIZES: [usize; 7] = [4, 6, 8, 10, 12, 14, 16];
+
+
+// Works with any 128 bit block cipher. Returns the ciphertext and a tag of
+// tag_len bytes
+pub fn encrypt(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag_len: usize)
+        -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    if !TAG_SIZES.contains(&tag_len) {
+        return Err(InvalidTagLen.into());
+    }
+    seal(data, cipher, nonce, aad, tag_len)
+}
+
+pub fn decrypt(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    if !TAG_SIZES.contains(&tag.len()) {
+        return Err(InvalidTagLen.into());
+    }
+    open(data, cipher, nonce, aad, tag)
+}
+
+// CCM* (IEEE 802.15.4) also allows omitting the tag for encryption only
+pub fn encrypt_star(data: &[u8], cipher: &impl Cipher<Block = Block>,
+        nonce: &[u8], aad: &[u8], tag_len: usize)
+        -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    if tag_len != 0 && !TAG_SIZES.contains(&tag_len) {
+        return Err(InvalidTagLen.into());
+    }
+    seal(data, cipher, nonce, aad, tag_len)
+}
+
+pub fn decrypt_star(data: &[u8], cipher: &impl Cipher<Block = Block>,
+        nonce: &[u8], aad: &[u8], tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    if !tag.is_empty() && !TAG_SIZES.contains(&tag.len()) {
+        return Err(InvalidTagLen.into());
+    }
+    open(data, cipher, nonce, aad, tag)
+}
+
+
+type Block = [u8; BLOCK_SIZE];
+
+fn seal(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag_len: usize)
+        -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    check_params(nonce, data.len())?;
+
+    let mac = cbc_mac(data, cipher, nonce, aad, tag_len);
+    let encrypted = ctr::keystream_xor(data, cipher, counter(nonce, 1),
+                                       1 + nonce.len());
+    let tag = ctr::keystream_xor(&mac[..tag_len], cipher, counter(nonce, 0),
+                                 1 + nonce.len());
+    Ok((encrypted, tag))
+}
+
+fn open(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    check_params(nonce, data.len())?;
+
+    let decrypted = ctr::keystream_xor(data, cipher, counter(nonce, 1),
+                                       1 + nonce.len());
+    let mac = cbc_mac(&decrypted, cipher, nonce, aad, tag.len());
+    let expected_tag = ctr::keystream_xor(&mac[..tag.len()], cipher,
+                                          counter(nonce, 0), 1 + nonce.len());
+    if !utils::constant_time_eq(&expected_tag, tag) {
+        return Err(AuthenticationFailed.into());
+    }
+    Ok(decrypted)
+}
+
+fn check_params(nonce: &[u8], data_len: usize) -> Result<(), AeadError> {
+    if nonce.len() < MIN_NONCE_SIZE || nonce.len() > MAX_NONCE_SIZE {
+        return Err(InvalidIvLen.into());
+    }
+    // The message length has to fit in the remaining q bytes of B0
+    let q = BLOCK_SIZE - 1 - nonce.len();
+    if q < 8 && (data_len as u64) >> (8 * q) != 0 {
+        return Err(InvalidDataLen.into());
+    }
+    Ok(())
+}
+
+// Counter block Ctr_i, flags only hold q - 1
+fn counter(nonce: &[u8], i: u64) -> Block {
+    let q = BLOCK_SIZE - 1 - nonce.len();
+    let mut block = [0; BLOCK_SIZE];
+    block[0] = (q - 1) as u8;
+    block[1..=nonce.len()].copy_from_slice(nonce);
+    block[BLOCK_SIZE-q..].copy_from_slice(&i.to_be_bytes()[8-q..]);
+    block
+}
+
+// CBC-MAC over B0, the encoded associated data and the payload, each zero
+// padded to a multiple of the block size
+fn cbc_mac(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag_len: usize) -> Block {
+    let q = BLOCK_SIZE - 1 - nonce.len();
+    let tag_flag = if tag_len == 0 { 0 } else { (tag_len - 2) / 2 };
+    let flags = (u8::from(!aad.is_empty()) << 6)
+              | ((tag_flag as u8) << 3)
+              | (q - 1) as u8;
+
+    let mut mac = [0; BLOCK_SIZE];
+    mac[0] = flags;
+    mac[1..=nonce.len()].copy_from_slice(nonce);
+    mac[BLOCK_SIZE-q..]
+        .copy_from_slice(&(data.len() as u64).to_be_bytes()[8-q..]);
+    cipher.encrypt_block(&mut mac);
+
+    if !aad.is_empty() {
+        // The first block starts with the encoded length
+        let mut block = [0; BLOCK_SIZE];
+        let len_size = if aad.len() < 0xff00 {
+            block[..2].copy_from_slice(&(aad.len() as u16).to_be_bytes());
+            2
+        } else if aad.len() as u64 <= u32::MAX as u64 {
+            block[..2].copy_from_slice(&[0xff, 0xfe]);
+            block[2..6].copy_from_slice(&(aad.len() as u32).to_be_bytes());
+            6
+        } else {
+            block[..2].copy_from_slice(&[0xff, 0xff]);
+            block[2..10].copy_from_slice(&(aad.len() as u64).to_be_bytes());
+            10
+        };
+        let (head, tail) = aad.split_at(aad.len().min(BLOCK_SIZE - len_size));
+        block[len_size..len_size+head.len()].copy_from_slice(head);
+        cbc_mac_update(&mut mac, cipher, &block);
+        cbc_mac_update(&mut mac, cipher, tail);
+    }
+    cbc_mac_update(&mut mac, cipher, data);
+
+    mac
+}
+
+// Partial blocks are implicitly zero padded
+fn cbc_mac_update(mac: &mut Block, cipher: &impl Cipher<Block = Block>,
+        data: &[u8]) {
+    for block in data.chunks(BLOCK_SIZE) {
+        utils::xor_slice(mac, block);
+        cipher.encrypt_block(mac);
+    }
+}
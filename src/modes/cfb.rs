@@ -0,0 +1,91 @@
+use crate::cipher::Cipher;
+use crate::error::InvalidIvLen;
+use crate::utils;
+
+
+// Full block CFB (CFB-128 for AES, CFB-64 for DES)
+pub fn encrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    cfb_bytes(data, cipher, iv, cipher.block_size(), true)
+}
+
+pub fn decrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    cfb_bytes(data, cipher, iv, cipher.block_size(), false)
+}
+
+pub fn encrypt_8(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    cfb_bytes(data, cipher, iv, 1, true)
+}
+
+pub fn decrypt_8(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    cfb_bytes(data, cipher, iv, 1, false)
+}
+
+// Bits are processed from the most significant bit of each byte
+pub fn encrypt_1(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    cfb_bits(data, cipher, iv, true)
+}
+
+pub fn decrypt_1(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    cfb_bits(data, cipher, iv, false)
+}
+
+
+fn cfb_bytes<C: Cipher>(data: &[u8], cipher: &C, iv: &[u8],
+        segment_size: usize, encrypting: bool)
+        -> Result<Vec<u8>,InvalidIvLen> {
+    let mut input_block = C::to_block(iv).map_err(|_| InvalidIvLen)?;
+    let mut output = data.to_vec();
+
+    for segment in output.chunks_mut(segment_size) {
+        let mut stream = input_block;
+        cipher.encrypt_block(&mut stream);
+
+        // Shift the input block and append the ciphertext segment
+        let input_block = input_block.as_mut();
+        input_block.rotate_left(segment.len());
+        let shift_start = input_block.len() - segment.len();
+        if !encrypting {
+            input_block[shift_start..].copy_from_slice(segment);
+        }
+        utils::xor_slice(segment, stream.as_ref());
+        if encrypting {
+            input_block[shift_start..].copy_from_slice(segment);
+        }
+    }
+
+    Ok(output)
+}
+
+fn cfb_bits<C: Cipher>(data: &[u8], cipher: &C, iv: &[u8], encrypting: bool)
+        -> Result<Vec<u8>,InvalidIvLen> {
+    let mut input_block = C::to_block(iv).map_err(|_| InvalidIvLen)?;
+    let mut output = vec![0; data.len()];
+
+    for i in 0..data.len()*8 {
+        let mut stream = input_block;
+        cipher.encrypt_block(&mut stream);
+        let bit = (data[i/8] >> (7 - i%8)) & 0x1;
+        let out_bit = bit ^ (stream.as_ref()[0] >> 7);
+        output[i/8] |= out_bit << (7 - i%8);
+
+        let ciphertext_bit = if encrypting { out_bit } else { bit };
+        shift_left_1(input_block.as_mut(), ciphertext_bit);
+    }
+
+    Ok(output)
+}
+
+fn shift_left_1(block: &mut [u8], lsb: u8) {
+    let mut carry = lsb;
+    for byte in block.iter_mut().rev() {
+        let next_carry = *byte >> 7;
+        *byte = (*byte << 1) | carry;
+        carry = next_carry;
+    }
+}
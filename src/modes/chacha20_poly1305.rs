@@ -0,0 +1,74 @@
+use crate::cipher::{ChaCha20,XChaCha20};
+use crate::error::{AeadError,AuthenticationFailed,InvalidTagLen,ModeError};
+use crate::mac::{Mac,Poly1305};
+use crate::utils;
+
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+pub const XNONCE_SIZE: usize = 24;
+pub const TAG_SIZE: usize = 16;
+
+
+// RFC 8439 section 2.8, returns the ciphertext and the tag
+pub fn encrypt(data: &[u8], cipher: &ChaCha20, nonce: &[u8], aad: &[u8])
+        -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    let mut poly = poly1305_key_gen(cipher, nonce)?;
+    let encrypted = cipher.encrypt(data, nonce, 1).map_err(aead_error)?;
+    let tag = compute_tag(&mut poly, aad, &encrypted);
+    Ok((encrypted, tag))
+}
+
+pub fn decrypt(data: &[u8], cipher: &ChaCha20, nonce: &[u8], aad: &[u8],
+        tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    if tag.len() != TAG_SIZE {
+        return Err(InvalidTagLen.into());
+    }
+    let mut poly = poly1305_key_gen(cipher, nonce)?;
+    let expected_tag = compute_tag(&mut poly, aad, data);
+    if !utils::constant_time_eq(&expected_tag, tag) {
+        return Err(AuthenticationFailed.into());
+    }
+    cipher.decrypt(data, nonce, 1).map_err(aead_error)
+}
+
+// XChaCha20-Poly1305, 192 bit nonces
+pub fn encrypt_x(data: &[u8], cipher: &XChaCha20, nonce: &[u8], aad: &[u8])
+        -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    let (chacha, chacha_nonce) = cipher.derive(nonce)?;
+    encrypt(data, &chacha, &chacha_nonce, aad)
+}
+
+pub fn decrypt_x(data: &[u8], cipher: &XChaCha20, nonce: &[u8], aad: &[u8],
+        tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    let (chacha, chacha_nonce) = cipher.derive(nonce)?;
+    decrypt(data, &chacha, &chacha_nonce, aad, tag)
+}
+
+
+// One-time key is the first 32 bytes of the block with counter 0
+fn poly1305_key_gen(cipher: &ChaCha20, nonce: &[u8])
+        -> Result<Poly1305, AeadError> {
+    let key = cipher.encrypt(&[0; 32], nonce, 0).map_err(aead_error)?;
+    Ok(Poly1305::new(&key).expect("Invalid key"))
+}
+
+// ChaCha20 fails on the nonce size or on data exhausting the block counter
+fn aead_error(e: ModeError) -> AeadError {
+    match e {
+        ModeError::InvalidIvLen(e) => e.into(),
+        ModeError::InvalidDataLen(e) => e.into(),
+        ModeError::InvalidPadding(_) => unreachable!("ChaCha20 has no padding"),
+    }
+}
+
+fn compute_tag(poly: &mut Poly1305, aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let padding = [0u8; 16];
+    poly.update(aad);
+    poly.update(&padding[..(16 - aad.len() % 16) % 16]);
+    poly.update(ciphertext);
+    poly.update(&padding[..(16 - ciphertext.len() % 16) % 16]);
+    poly.update(&(aad.len() as u64).to_le_bytes());
+    poly.update(&(ciphertext.len() as u64).to_le_bytes());
+    poly.generate()
+}
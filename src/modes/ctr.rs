@@ -1,29 +1,13 @@
 use crate::cipher::Cipher;
 use crate::error::InvalidIvLen;
 use crate::utils;
+use super::PARALLEL_BLOCKS;
 
 
 pub fn encrypt(data: &[u8], cipher: &impl Cipher, nonce: &[u8])
         -> Result<Vec<u8>,InvalidIvLen> {
-    let nonce_size: usize = cipher.block_size() / 2;
-    if nonce.len() != nonce_size {
-        return Err(InvalidIvLen);
-    }
-    let mut counter: u64 = 0;
-    let mut encrypted: Vec<u8> = Vec::with_capacity(data.len());
-    let mut nonce_counter: Vec<u8> = vec![0; cipher.block_size()];
-    nonce_counter[..nonce_size].clone_from_slice(nonce);
-
-    for block in data.chunks(cipher.block_size()) {
-        let counter_bytes = &counter.to_be_bytes()[8-nonce_size..];
-        nonce_counter[nonce_size..].clone_from_slice(counter_bytes);
-        let mut stream = cipher.encrypt(nonce_counter.as_slice())
-            .expect("Invalid nonce_counter size");
-        utils::xor_slice(stream.as_mut_slice(), block);
-        encrypted.extend_from_slice(&stream[..block.len()]);
-        counter += 1;
-    }
-
+    let mut encrypted = data.to_vec();
+    apply_keystream(&mut encrypted, cipher, nonce)?;
     Ok(encrypted)
 }
 
@@ -31,3 +15,54 @@ pub fn decrypt(data: &[u8], cipher: &impl Cipher, nonce: &[u8])
         -> Result<Vec<u8>,InvalidIvLen> {
     encrypt(data, cipher, nonce)
 }
+
+// Encrypts or decrypts in place
+pub fn apply_keystream<C: Cipher>(data: &mut [u8], cipher: &C, nonce: &[u8])
+        -> Result<(),InvalidIvLen> {
+    let nonce_size: usize = C::BLOCK_SIZE / 2;
+    if nonce.len() != nonce_size {
+        return Err(InvalidIvLen);
+    }
+    let mut nonce_counter = C::Block::default();
+    nonce_counter.as_mut()[..nonce_size].clone_from_slice(nonce);
+
+    keystream_xor_in_place(data, cipher, nonce_counter, nonce_size);
+    Ok(())
+}
+
+// XORs data with the keystream generated from the initial counter block,
+// the counter is formed by the bytes after counter_start and wraps around
+pub(super) fn keystream_xor<C: Cipher>(data: &[u8], cipher: &C,
+        counter_block: C::Block, counter_start: usize) -> Vec<u8> {
+    let mut encrypted = data.to_vec();
+    keystream_xor_in_place(&mut encrypted, cipher, counter_block,
+                           counter_start);
+    encrypted
+}
+
+pub(super) fn keystream_xor_in_place<C: Cipher>(data: &mut [u8], cipher: &C,
+        mut nonce_counter: C::Block, counter_start: usize) {
+    let mut stream = [C::Block::default(); PARALLEL_BLOCKS];
+
+    // Counter blocks are encrypted in groups so the cipher can pipeline them
+    for group in data.chunks_mut(PARALLEL_BLOCKS * C::BLOCK_SIZE) {
+        let n = group.len().div_ceil(C::BLOCK_SIZE);
+        for block in stream[..n].iter_mut() {
+            *block = nonce_counter;
+            increment_counter(&mut nonce_counter.as_mut()[counter_start..]);
+        }
+        cipher.encrypt_blocks(&mut stream[..n]);
+        for (chunk, block) in group.chunks_mut(C::BLOCK_SIZE).zip(&stream) {
+            utils::xor_slice(chunk, block.as_ref());
+        }
+    }
+}
+
+fn increment_counter(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
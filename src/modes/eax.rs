@@ -0,0 +1,74 @@
+use crate::cipher::Cipher;
+use crate::error::{AeadError,AuthenticationFailed,InvalidTagLen,KeyError};
+use crate::mac::{Mac,Cmac};
+use crate::utils;
+use super::ctr;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+
+// OMAC tweaks for the nonce, the header and the ciphertext
+const NONCE_TWEAK: u8 = 0;
+const HEADER_TWEAK: u8 = 1;
+const CIPHERTEXT_TWEAK: u8 = 2;
+
+
+// Bellare, Rogaway and Wagner EAX mode, the tag is N' xor H' xor C'. Works
+// with any 128 bit block cipher
+pub struct Eax<C> where C: Cipher<Block = Block> {
+    cmac: Cmac<C>,
+    cipher: C,
+}
+
+type Block = [u8; BLOCK_SIZE];
+
+impl<C> Eax<C> where C: Cipher<Block = Block> {
+    pub fn new(key: &[u8]) -> Result<Self, KeyError> {
+        let cmac = Cmac::<C>::new(key)?;
+        let cipher = C::new(key)?;
+        Ok(Self { cmac, cipher })
+    }
+
+    // Returns the ciphertext and a tag of tag_len bytes
+    pub fn encrypt(&mut self, data: &[u8], nonce: &[u8], aad: &[u8],
+            tag_len: usize) -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+        if tag_len == 0 || tag_len > TAG_SIZE {
+            return Err(InvalidTagLen.into());
+        }
+        let n = self.omac(NONCE_TWEAK, nonce);
+        let encrypted = ctr::keystream_xor(data, &self.cipher, n, 0);
+
+        let tag = self.tag(n, aad, &encrypted);
+        Ok((encrypted, tag[..tag_len].to_vec()))
+    }
+
+    pub fn decrypt(&mut self, data: &[u8], nonce: &[u8], aad: &[u8],
+            tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if tag.is_empty() || tag.len() > TAG_SIZE {
+            return Err(InvalidTagLen.into());
+        }
+        let n = self.omac(NONCE_TWEAK, nonce);
+
+        let expected_tag = self.tag(n, aad, data);
+        if !utils::constant_time_eq(&expected_tag[..tag.len()], tag) {
+            return Err(AuthenticationFailed.into());
+        }
+        Ok(ctr::keystream_xor(data, &self.cipher, n, 0))
+    }
+
+    // OMAC^t(M) = CMAC([t]_n || M)
+    fn omac(&mut self, tweak: u8, data: &[u8]) -> Block {
+        let mut block = [0; BLOCK_SIZE];
+        block[BLOCK_SIZE-1] = tweak;
+        self.cmac.update(&block);
+        self.cmac.update(data);
+        self.cmac.generate_block()
+    }
+
+    fn tag(&mut self, mut n: Block, aad: &[u8], ciphertext: &[u8]) -> Block {
+        utils::xor_slice(&mut n, &self.omac(HEADER_TWEAK, aad));
+        utils::xor_slice(&mut n, &self.omac(CIPHERTEXT_TWEAK, ciphertext));
+        n
+    }
+}
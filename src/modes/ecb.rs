@@ -1,33 +1,63 @@
-use crate::cipher::Cipher;
-use crate::error::InvalidIvLen;
-use super::pkcs7_padding;
+use crate::cipher::{BlockArray,Cipher};
+use crate::error::{InvalidDataLen,ModeError};
+use super::padding::{Padding,Pkcs7};
+use super::PARALLEL_BLOCKS;
 
 
 pub fn encrypt(data: &[u8], cipher: &impl Cipher)
-        -> Result<Vec<u8>, InvalidIvLen> {
-    let padded = pkcs7_padding::pad(data, cipher.block_size());
-    let mut encrypted = Vec::<u8>::with_capacity(padded.len());
-
-    for block in padded.chunks_exact(cipher.block_size()) {
-        let ciphertext = cipher.encrypt(block)
-            .expect("Invalid block size");
-        encrypted.extend_from_slice(ciphertext.as_slice());
-    }
-
-    Ok(encrypted)
+        -> Result<Vec<u8>, ModeError> {
+    encrypt_padded::<Pkcs7>(data, cipher)
 }
 
 pub fn decrypt(data: &[u8], cipher: &impl Cipher)
-            -> Result<Vec<u8>, InvalidIvLen> {
-    let mut decrypted = Vec::<u8>::with_capacity(data.len());
+        -> Result<Vec<u8>, ModeError> {
+    decrypt_padded::<Pkcs7>(data, cipher)
+}
 
-    for block in data.chunks_exact(cipher.block_size()) {
-        let plaintext = cipher.decrypt(block)
-            .expect("Invalid block size");
-        decrypted.extend_from_slice(plaintext.as_slice());
-    }
+pub fn encrypt_padded<P: Padding>(data: &[u8], cipher: &impl Cipher)
+        -> Result<Vec<u8>, ModeError> {
+    let mut encrypted = P::pad(data, cipher.block_size())?;
+    encrypt_in_place(&mut encrypted, cipher)?;
+    Ok(encrypted)
+}
 
-    let unpadded = pkcs7_padding::unpad(decrypted.as_slice());
+pub fn decrypt_padded<P: Padding>(data: &[u8], cipher: &impl Cipher)
+        -> Result<Vec<u8>, ModeError> {
+    let mut decrypted = data.to_vec();
+    decrypt_in_place(&mut decrypted, cipher)?;
+
+    let unpadded = P::unpad(decrypted.as_slice(), cipher.block_size())?;
     decrypted.truncate(unpadded.len());
     Ok(decrypted)
 }
+
+// Unpadded variants, data must be a multiple of the block size
+pub fn encrypt_in_place(data: &mut [u8], cipher: &impl Cipher)
+        -> Result<(), InvalidDataLen> {
+    process_in_place(data, cipher, true)
+}
+
+pub fn decrypt_in_place(data: &mut [u8], cipher: &impl Cipher)
+        -> Result<(), InvalidDataLen> {
+    process_in_place(data, cipher, false)
+}
+
+
+// Blocks are handed to the cipher in groups so it can process them in
+// parallel
+fn process_in_place<C: Cipher>(data: &mut [u8], cipher: &C, encrypting: bool)
+        -> Result<(), InvalidDataLen> {
+    let (blocks, []) = C::Block::split_mut(data) else {
+        return Err(InvalidDataLen);
+    };
+
+    for group in blocks.chunks_mut(PARALLEL_BLOCKS) {
+        if encrypting {
+            cipher.encrypt_blocks(group);
+        } else {
+            cipher.decrypt_blocks(group);
+        }
+    }
+
+    Ok(())
+}
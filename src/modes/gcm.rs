@@ -0,0 +1,143 @@
+use crate::cipher::Cipher;
+use crate::error::{AeadError,AuthenticationFailed,InvalidIvLen,InvalidTagLen};
+use crate::utils;
+use super::ctr;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 12; // Recommended IV size
+
+// Allowed truncated tag sizes (SP 800-38D 5.2.1.2)
+const TAG_SIZES: [usize; 7] = [16, 15, 14, 13, 12, 8, 4];
+
+// GF(2^128) reduction polynomial, x^128 + x^7 + x^2 + x + 1 (bit reflected)
+const R: u128 = 0xE1 << 120;
+
+
+// Works with any 128 bit block cipher. Returns the ciphertext and a tag of
+// tag_len bytes
+pub fn encrypt(data: &[u8], cipher: &impl Cipher<Block = Block>, iv: &[u8],
+        aad: &[u8], tag_len: usize) -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    if !TAG_SIZES.contains(&tag_len) {
+        return Err(InvalidTagLen.into());
+    }
+    let (h, j0) = init(cipher, iv)?;
+
+    let mut counter = j0;
+    increment_32(&mut counter);
+    let encrypted = ctr::keystream_xor(data, cipher, counter, BLOCK_SIZE-4);
+
+    let mut tag = compute_tag(cipher, h, &j0, aad, &encrypted);
+    tag.truncate(tag_len);
+    Ok((encrypted, tag))
+}
+
+pub fn decrypt(data: &[u8], cipher: &impl Cipher<Block = Block>, iv: &[u8],
+        aad: &[u8], tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    if !TAG_SIZES.contains(&tag.len()) {
+        return Err(InvalidTagLen.into());
+    }
+    let (h, j0) = init(cipher, iv)?;
+
+    let expected_tag = compute_tag(cipher, h, &j0, aad, data);
+    if !utils::constant_time_eq(&expected_tag[..tag.len()], tag) {
+        return Err(AuthenticationFailed.into());
+    }
+
+    let mut counter = j0;
+    increment_32(&mut counter);
+    Ok(ctr::keystream_xor(data, cipher, counter, BLOCK_SIZE-4))
+}
+
+
+type Block = [u8; BLOCK_SIZE];
+
+// Computes the hash subkey H and the pre-counter block J0
+fn init(cipher: &impl Cipher<Block = Block>, iv: &[u8])
+        -> Result<(u128, Block), AeadError> {
+    if iv.is_empty() {
+        return Err(InvalidIvLen.into());
+    }
+
+    let mut h = [0; BLOCK_SIZE];
+    cipher.encrypt_block(&mut h);
+    let h = u128::from_be_bytes(h);
+
+    let mut j0 = [0; BLOCK_SIZE];
+    if iv.len() == NONCE_SIZE {
+        j0[..NONCE_SIZE].copy_from_slice(iv);
+        j0[BLOCK_SIZE-1] = 1;
+    } else {
+        let mut ghash = Ghash::new(h);
+        ghash.update(iv);
+        ghash.update_u128((iv.len() as u128) * 8);
+        j0 = ghash.finalize().to_be_bytes();
+    }
+
+    Ok((h, j0))
+}
+
+fn compute_tag(cipher: &impl Cipher<Block = Block>, h: u128, j0: &Block,
+        aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut ghash = Ghash::new(h);
+    ghash.update(aad);
+    ghash.update(ciphertext);
+    let lengths = ((aad.len() as u128 * 8) << 64)
+                | (ciphertext.len() as u128 * 8);
+    ghash.update_u128(lengths);
+    let s = ghash.finalize().to_be_bytes();
+
+    ctr::keystream_xor(&s, cipher, *j0, BLOCK_SIZE-4)
+}
+
+fn increment_32(counter: &mut Block) {
+    let counter_32 = &mut counter[BLOCK_SIZE-4..];
+    let value = u32::from_be_bytes((*counter_32).try_into().unwrap());
+    counter_32.copy_from_slice(&value.wrapping_add(1).to_be_bytes());
+}
+
+
+pub(super) fn gf_mult(x: u128, y: u128) -> u128 {
+    let mut z: u128 = 0;
+    let mut v: u128 = y;
+    for i in (0..128).rev() {
+        if (x >> i) & 1 == 1 {
+            z ^= v;
+        }
+        if v & 1 == 1 {
+            v = (v >> 1) ^ R;
+        } else {
+            v >>= 1;
+        }
+    }
+    z
+}
+
+pub(super) struct Ghash {
+    h: u128,
+    y: u128,
+}
+
+impl Ghash {
+    pub(super) fn new(h: u128) -> Self {
+        Self { h, y: 0 }
+    }
+
+    // Data is zero padded up to a multiple of the block size
+    fn update(&mut self, data: &[u8]) {
+        for block in data.chunks(BLOCK_SIZE) {
+            let mut padded = [0; BLOCK_SIZE];
+            padded[..block.len()].copy_from_slice(block);
+            self.update_u128(u128::from_be_bytes(padded));
+        }
+    }
+
+    pub(super) fn update_u128(&mut self, block: u128) {
+        self.y = gf_mult(self.y ^ block, self.h);
+    }
+
+    pub(super) fn finalize(&self) -> u128 {
+        self.y
+    }
+}
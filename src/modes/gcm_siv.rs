@@ -0,0 +1,160 @@
+use crate::cipher::Cipher;
+use crate::error::{AeadError,AuthenticationFailed,InvalidDataLen,InvalidIvLen,
+                   InvalidKeyLen,InvalidTagLen,KeyError};
+use crate::utils;
+use super::gcm::{gf_mult,Ghash};
+use super::PARALLEL_BLOCKS;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 12;
+
+const KEY_SIZES: [usize; 2] = [16, 32];
+// Plaintext and associated data are limited to 2^36 bytes
+const MAX_DATA_LEN: u64 = 1 << 36;
+
+
+// RFC 8452, the key generating key derives per nonce authentication and
+// encryption keys of the same size. Works with any 128 bit block cipher
+pub struct GcmSiv<C> where C: Cipher<Block = Block> {
+    cipher: C,
+    key_len: usize,
+}
+
+type Block = [u8; BLOCK_SIZE];
+
+impl<C> GcmSiv<C> where C: Cipher<Block = Block> {
+    pub fn new(key: &[u8]) -> Result<Self, KeyError> {
+        if !KEY_SIZES.contains(&key.len()) {
+            return Err(InvalidKeyLen.into());
+        }
+        let cipher = C::new(key)?;
+        Ok(Self { cipher, key_len: key.len() })
+    }
+
+    // Returns the ciphertext and the tag
+    pub fn encrypt(&self, data: &[u8], nonce: &[u8], aad: &[u8])
+            -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+        check_lengths(data, nonce, aad)?;
+        let (auth_key, cipher) = self.derive_keys(nonce);
+
+        let tag = compute_tag(&cipher, auth_key, nonce, aad, data);
+        let encrypted = keystream_xor(data, &cipher, &tag);
+        Ok((encrypted, tag.to_vec()))
+    }
+
+    pub fn decrypt(&self, data: &[u8], nonce: &[u8], aad: &[u8], tag: &[u8])
+            -> Result<Vec<u8>, AeadError> {
+        check_lengths(data, nonce, aad)?;
+        let tag: &[u8; TAG_SIZE] = tag.try_into().map_err(|_| InvalidTagLen)?;
+        let (auth_key, cipher) = self.derive_keys(nonce);
+
+        let decrypted = keystream_xor(data, &cipher, tag);
+        let expected_tag = compute_tag(&cipher, auth_key, nonce, aad,
+                                       &decrypted);
+        if !utils::constant_time_eq(&expected_tag, tag) {
+            return Err(AuthenticationFailed.into());
+        }
+        Ok(decrypted)
+    }
+
+    // Each key is built from the first half of encrypted counter blocks
+    fn derive_keys(&self, nonce: &[u8]) -> (Block, C) {
+        let mut derived = Vec::<u8>::with_capacity(BLOCK_SIZE + self.key_len);
+        for i in 0..(BLOCK_SIZE + self.key_len) / 8 {
+            let mut block = [0; BLOCK_SIZE];
+            block[..4].copy_from_slice(&(i as u32).to_le_bytes());
+            block[4..].copy_from_slice(nonce);
+            self.cipher.encrypt_block(&mut block);
+            derived.extend_from_slice(&block[..8]);
+        }
+
+        let (auth_key, key) = derived.split_at(BLOCK_SIZE);
+        let cipher = C::new(key).expect("Invalid derived key size");
+        (auth_key.try_into().unwrap(), cipher)
+    }
+}
+
+
+fn check_lengths(data: &[u8], nonce: &[u8], aad: &[u8])
+        -> Result<(), AeadError> {
+    if nonce.len() != NONCE_SIZE {
+        return Err(InvalidIvLen.into());
+    }
+    if data.len() as u64 > MAX_DATA_LEN || aad.len() as u64 > MAX_DATA_LEN {
+        return Err(InvalidDataLen.into());
+    }
+    Ok(())
+}
+
+fn compute_tag(cipher: &impl Cipher<Block = Block>, auth_key: Block,
+        nonce: &[u8], aad: &[u8], data: &[u8]) -> Block {
+    let mut polyval = Polyval::new(auth_key);
+    polyval.update(aad);
+    polyval.update(data);
+    let lengths = ((data.len() as u128 * 8) << 64) | (aad.len() as u128 * 8);
+    polyval.update_block(lengths.to_le_bytes());
+
+    let mut s = polyval.finalize();
+    utils::xor_slice(&mut s, nonce);
+    s[BLOCK_SIZE-1] &= 0x7f;
+    cipher.encrypt_block(&mut s);
+    s
+}
+
+// The counter is the first 32 bits of the block as a little endian integer
+fn keystream_xor(data: &[u8], cipher: &impl Cipher<Block = Block>,
+        tag: &Block) -> Vec<u8> {
+    let mut encrypted = data.to_vec();
+    let mut counter_block = *tag;
+    counter_block[BLOCK_SIZE-1] |= 0x80;
+    let mut stream = [[0; BLOCK_SIZE]; PARALLEL_BLOCKS];
+
+    for group in encrypted.chunks_mut(PARALLEL_BLOCKS * BLOCK_SIZE) {
+        let n = group.len().div_ceil(BLOCK_SIZE);
+        for block in stream[..n].iter_mut() {
+            *block = counter_block;
+            let counter = &mut counter_block[..4];
+            let value = u32::from_le_bytes((*counter).try_into().unwrap());
+            counter.copy_from_slice(&value.wrapping_add(1).to_le_bytes());
+        }
+        cipher.encrypt_blocks(&mut stream[..n]);
+        for (chunk, block) in group.chunks_mut(BLOCK_SIZE).zip(&stream) {
+            utils::xor_slice(chunk, block);
+        }
+    }
+
+    encrypted
+}
+
+
+// POLYVAL computed through GHASH on byte reversed blocks, with the key
+// multiplied by x (RFC 8452 Appendix A)
+struct Polyval {
+    ghash: Ghash,
+}
+
+impl Polyval {
+    fn new(h: Block) -> Self {
+        let h = gf_mult(u128::from_le_bytes(h), 1 << 126);
+        Self { ghash: Ghash::new(h) }
+    }
+
+    // Data is zero padded up to a multiple of the block size
+    fn update(&mut self, data: &[u8]) {
+        for block in data.chunks(BLOCK_SIZE) {
+            let mut padded = [0; BLOCK_SIZE];
+            padded[..block.len()].copy_from_slice(block);
+            self.update_block(padded);
+        }
+    }
+
+    fn update_block(&mut self, block: Block) {
+        self.ghash.update_u128(u128::from_le_bytes(block));
+    }
+
+    fn finalize(&self) -> Block {
+        self.ghash.finalize().to_le_bytes()
+    }
+}
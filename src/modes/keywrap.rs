@@ -0,0 +1,146 @@
+use crate::cipher::Cipher;
+use crate::error::{IntegrityCheckFailed,InvalidDataLen,KeyWrapError};
+use crate::utils;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const SEMIBLOCK_SIZE: usize = 8;
+
+// RFC 3394 default initial value
+pub const DEFAULT_IV: [u8; SEMIBLOCK_SIZE] = [0xA6; SEMIBLOCK_SIZE];
+// RFC 5649 alternative initial value prefix
+pub const PADDED_IV_PREFIX: [u8; 4] = [0xA6, 0x59, 0x59, 0xA6];
+
+
+type Block = [u8; BLOCK_SIZE];
+type Semiblock = [u8; SEMIBLOCK_SIZE];
+
+
+// RFC 3394 key wrap with any 128 bit block cipher, the key data must be at
+// least two 64 bit blocks
+pub fn wrap(data: &[u8], cipher: &impl Cipher<Block = Block>)
+        -> Result<Vec<u8>, KeyWrapError> {
+    if data.len() < 2*SEMIBLOCK_SIZE
+            || !data.len().is_multiple_of(SEMIBLOCK_SIZE) {
+        return Err(InvalidDataLen.into());
+    }
+    Ok(wrap_core(data, cipher, DEFAULT_IV))
+}
+
+pub fn unwrap(data: &[u8], cipher: &impl Cipher<Block = Block>)
+        -> Result<Vec<u8>, KeyWrapError> {
+    if data.len() < 3*SEMIBLOCK_SIZE
+            || !data.len().is_multiple_of(SEMIBLOCK_SIZE) {
+        return Err(InvalidDataLen.into());
+    }
+    let (iv, unwrapped) = unwrap_core(data, cipher);
+    if !utils::constant_time_eq(&iv, &DEFAULT_IV) {
+        return Err(IntegrityCheckFailed.into());
+    }
+    Ok(unwrapped)
+}
+
+// RFC 5649 key wrap with padding, accepts key data of any non zero length
+pub fn wrap_padded(data: &[u8], cipher: &impl Cipher<Block = Block>)
+        -> Result<Vec<u8>, KeyWrapError> {
+    let mli = u32::try_from(data.len()).map_err(|_| InvalidDataLen)?;
+    if mli == 0 {
+        return Err(InvalidDataLen.into());
+    }
+
+    let mut iv: Semiblock = [0; SEMIBLOCK_SIZE];
+    iv[..4].copy_from_slice(&PADDED_IV_PREFIX);
+    iv[4..].copy_from_slice(&mli.to_be_bytes());
+
+    let mut padded = data.to_vec();
+    padded.resize(data.len().div_ceil(SEMIBLOCK_SIZE) * SEMIBLOCK_SIZE, 0);
+
+    if padded.len() == SEMIBLOCK_SIZE {
+        // A single block is encrypted directly
+        let mut block = [0; BLOCK_SIZE];
+        block[..SEMIBLOCK_SIZE].copy_from_slice(&iv);
+        block[SEMIBLOCK_SIZE..].copy_from_slice(&padded);
+        cipher.encrypt_block(&mut block);
+        return Ok(block.to_vec());
+    }
+    Ok(wrap_core(&padded, cipher, iv))
+}
+
+pub fn unwrap_padded(data: &[u8], cipher: &impl Cipher<Block = Block>)
+        -> Result<Vec<u8>, KeyWrapError> {
+    if data.len() < 2*SEMIBLOCK_SIZE
+            || !data.len().is_multiple_of(SEMIBLOCK_SIZE) {
+        return Err(InvalidDataLen.into());
+    }
+
+    let (iv, mut unwrapped) = if let Ok(mut block) = Block::try_from(data) {
+        cipher.decrypt_block(&mut block);
+        let (iv, unwrapped) = block.split_at(SEMIBLOCK_SIZE);
+        (iv.try_into().unwrap(), unwrapped.to_vec())
+    } else {
+        unwrap_core(data, cipher)
+    };
+
+    if !utils::constant_time_eq(&iv[..4], &PADDED_IV_PREFIX) {
+        return Err(IntegrityCheckFailed.into());
+    }
+    let mli = u32::from_be_bytes(iv[4..].try_into().unwrap()) as usize;
+    if mli > unwrapped.len() || mli + SEMIBLOCK_SIZE <= unwrapped.len() {
+        return Err(IntegrityCheckFailed.into());
+    }
+    if unwrapped[mli..].iter().any(|&b| b != 0) {
+        return Err(IntegrityCheckFailed.into());
+    }
+
+    unwrapped.truncate(mli);
+    Ok(unwrapped)
+}
+
+
+fn wrap_core(data: &[u8], cipher: &impl Cipher<Block = Block>, iv: Semiblock)
+        -> Vec<u8> {
+    let mut a = iv;
+    let mut r = data.as_chunks::<SEMIBLOCK_SIZE>().0.to_vec();
+    let n = r.len() as u64;
+
+    for j in 0..6 {
+        for (i, ri) in r.iter_mut().enumerate() {
+            let mut b = [0; BLOCK_SIZE];
+            b[..SEMIBLOCK_SIZE].copy_from_slice(&a);
+            b[SEMIBLOCK_SIZE..].copy_from_slice(ri);
+            cipher.encrypt_block(&mut b);
+            let t = n*j + i as u64 + 1;
+            a.copy_from_slice(&b[..SEMIBLOCK_SIZE]);
+            utils::xor_slice(&mut a, &t.to_be_bytes());
+            ri.copy_from_slice(&b[SEMIBLOCK_SIZE..]);
+        }
+    }
+
+    let mut wrapped = a.to_vec();
+    wrapped.extend(r.iter().flatten());
+    wrapped
+}
+
+// Returns the recovered initial value and the unwrapped data
+fn unwrap_core(data: &[u8], cipher: &impl Cipher<Block = Block>)
+        -> (Semiblock, Vec<u8>) {
+    let (semiblocks, _) = data.as_chunks::<SEMIBLOCK_SIZE>();
+    let mut a = semiblocks[0];
+    let mut r = semiblocks[1..].to_vec();
+    let n = r.len() as u64;
+
+    for j in (0..6).rev() {
+        for (i, ri) in r.iter_mut().enumerate().rev() {
+            let t = n*j + i as u64 + 1;
+            utils::xor_slice(&mut a, &t.to_be_bytes());
+            let mut b = [0; BLOCK_SIZE];
+            b[..SEMIBLOCK_SIZE].copy_from_slice(&a);
+            b[SEMIBLOCK_SIZE..].copy_from_slice(ri);
+            cipher.decrypt_block(&mut b);
+            a.copy_from_slice(&b[..SEMIBLOCK_SIZE]);
+            ri.copy_from_slice(&b[SEMIBLOCK_SIZE..]);
+        }
+    }
+
+    (a, r.iter().flatten().copied().collect())
+}
@@ -0,0 +1,201 @@
+use crate::cipher::Cipher;
+use crate::error::{AeadError,AuthenticationFailed,InvalidIvLen,InvalidTagLen};
+use crate::mac::double;
+use crate::utils;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 12;
+
+const MAX_NONCE_SIZE: usize = 15;
+
+
+// RFC 7253 OCB3 with any 128 bit block cipher, returns the ciphertext and a
+// tag of tag_len bytes
+pub fn encrypt(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag_len: usize)
+        -> Result<(Vec<u8>, Vec<u8>), AeadError> {
+    let mut ocb = Ocb::new(cipher, nonce, tag_len)?;
+    let mut encrypted = data.to_vec();
+    let mut checksum = [0; BLOCK_SIZE];
+
+    let (blocks, partial) = encrypted.as_chunks_mut::<BLOCK_SIZE>();
+    for (i, block) in blocks.iter_mut().enumerate() {
+        ocb.next_offset(i + 1);
+        utils::xor_slice(&mut checksum, block);
+        ocb.xex(block, true);
+    }
+
+    if !partial.is_empty() {
+        utils::xor_slice(&mut checksum, &pad(partial));
+        ocb.process_partial(partial);
+    }
+
+    let tag = ocb.tag(&checksum, aad);
+    Ok((encrypted, tag))
+}
+
+pub fn decrypt(data: &[u8], cipher: &impl Cipher<Block = Block>, nonce: &[u8],
+        aad: &[u8], tag: &[u8]) -> Result<Vec<u8>, AeadError> {
+    let mut ocb = Ocb::new(cipher, nonce, tag.len())?;
+    let mut decrypted = data.to_vec();
+    let mut checksum = [0; BLOCK_SIZE];
+
+    let (blocks, partial) = decrypted.as_chunks_mut::<BLOCK_SIZE>();
+    for (i, block) in blocks.iter_mut().enumerate() {
+        ocb.next_offset(i + 1);
+        ocb.xex(block, false);
+        utils::xor_slice(&mut checksum, block);
+    }
+
+    if !partial.is_empty() {
+        ocb.process_partial(partial);
+        utils::xor_slice(&mut checksum, &pad(partial));
+    }
+
+    let expected_tag = ocb.tag(&checksum, aad);
+    if !utils::constant_time_eq(&expected_tag, tag) {
+        return Err(AuthenticationFailed.into());
+    }
+    Ok(decrypted)
+}
+
+
+type Block = [u8; BLOCK_SIZE];
+
+struct Ocb<'a, C> where C: Cipher<Block = Block> {
+    cipher: &'a C,
+    l_star: Block,
+    l_dollar: Block,
+    // L_i values, computed as needed
+    l: Vec<Block>,
+    offset: Block,
+    tag_len: usize,
+}
+
+impl<'a, C> Ocb<'a, C> where C: Cipher<Block = Block> {
+    fn new(cipher: &'a C, nonce: &[u8], tag_len: usize)
+            -> Result<Self, AeadError> {
+        if nonce.is_empty() || nonce.len() > MAX_NONCE_SIZE {
+            return Err(InvalidIvLen.into());
+        }
+        if tag_len == 0 || tag_len > TAG_SIZE {
+            return Err(InvalidTagLen.into());
+        }
+
+        let mut l_star = [0; BLOCK_SIZE];
+        cipher.encrypt_block(&mut l_star);
+        let mut l_dollar = l_star;
+        double(&mut l_dollar);
+        let mut l_0 = l_dollar;
+        double(&mut l_0);
+        let l = vec![l_0];
+
+        // Nonce = num2str(TAGLEN mod 128, 7) || zeros || 1 || N
+        let mut full_nonce = [0; BLOCK_SIZE];
+        full_nonce[BLOCK_SIZE-nonce.len()..].copy_from_slice(nonce);
+        full_nonce[BLOCK_SIZE-nonce.len()-1] |= 1;
+        full_nonce[0] |= (((tag_len * 8) % 128) as u8) << 1;
+
+        let bottom = (full_nonce[BLOCK_SIZE-1] & 0x3f) as u32;
+        full_nonce[BLOCK_SIZE-1] &= 0xc0;
+        let mut ktop = full_nonce;
+        cipher.encrypt_block(&mut ktop);
+
+        // Stretch = Ktop || (Ktop[1..64] xor Ktop[9..72])
+        let mut stretch_tail = [0; 8];
+        for i in 0..8 {
+            stretch_tail[i] = ktop[i] ^ ktop[i+1];
+        }
+        let ktop = u128::from_be_bytes(ktop);
+        let stretch_tail = u64::from_be_bytes(stretch_tail) as u128;
+        let offset = (ktop << bottom) | (stretch_tail >> (64 - bottom));
+
+        Ok(Self {
+            cipher,
+            l_star,
+            l_dollar,
+            l,
+            offset: offset.to_be_bytes(),
+            tag_len,
+        })
+    }
+
+    fn l_value(&mut self, i: usize) -> Block {
+        while self.l.len() <= i {
+            let mut next = *self.l.last().unwrap();
+            double(&mut next);
+            self.l.push(next);
+        }
+        self.l[i]
+    }
+
+    // Offset_i = Offset_{i-1} xor L_{ntz(i)}
+    fn next_offset(&mut self, i: usize) {
+        let l = self.l_value(i.trailing_zeros() as usize);
+        utils::xor_slice(&mut self.offset, &l);
+    }
+
+    // Offset xor E(block xor Offset), in place
+    fn xex(&self, block: &mut Block, encrypting: bool) {
+        utils::xor_slice(block, &self.offset);
+        if encrypting {
+            self.cipher.encrypt_block(block);
+        } else {
+            self.cipher.decrypt_block(block);
+        }
+        utils::xor_slice(block, &self.offset);
+    }
+
+    // The final partial block is XORed with a pad, in both directions
+    fn process_partial(&mut self, partial: &mut [u8]) {
+        utils::xor_slice(&mut self.offset, &self.l_star);
+        let mut pad = self.offset;
+        self.cipher.encrypt_block(&mut pad);
+        utils::xor_slice(partial, &pad);
+    }
+
+    fn tag(&mut self, checksum: &Block, aad: &[u8]) -> Vec<u8> {
+        let mut tag = *checksum;
+        utils::xor_slice(&mut tag, &self.offset);
+        utils::xor_slice(&mut tag, &self.l_dollar);
+        self.cipher.encrypt_block(&mut tag);
+        utils::xor_slice(&mut tag, &self.hash(aad));
+        tag[..self.tag_len].to_vec()
+    }
+
+    fn hash(&mut self, aad: &[u8]) -> Block {
+        let mut offset = [0; BLOCK_SIZE];
+        let mut sum = [0; BLOCK_SIZE];
+
+        let (blocks, partial) = aad.as_chunks::<BLOCK_SIZE>();
+        for (i, block) in blocks.iter().enumerate() {
+            let l = self.l_value((i + 1).trailing_zeros() as usize);
+            utils::xor_slice(&mut offset, &l);
+            let mut input = offset;
+            utils::xor_slice(&mut input, block);
+            self.cipher.encrypt_block(&mut input);
+            utils::xor_slice(&mut sum, &input);
+        }
+
+        if !partial.is_empty() {
+            utils::xor_slice(&mut offset, &self.l_star);
+            let mut input = pad(partial);
+            utils::xor_slice(&mut input, &offset);
+            self.cipher.encrypt_block(&mut input);
+            utils::xor_slice(&mut sum, &input);
+        }
+
+        sum
+    }
+}
+
+// 10* padding of a partial block
+fn pad(partial: &[u8]) -> Block {
+    let mut padded = [0; BLOCK_SIZE];
+    padded[..partial.len()].copy_from_slice(partial);
+    padded[partial.len()] = 0x80;
+    padded
+}
+
@@ -0,0 +1,29 @@
+use crate::cipher::Cipher;
+use crate::error::InvalidIvLen;
+use crate::utils;
+
+
+pub fn encrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    let mut encrypted = data.to_vec();
+    apply_keystream(&mut encrypted, cipher, iv)?;
+    Ok(encrypted)
+}
+
+pub fn decrypt(data: &[u8], cipher: &impl Cipher, iv: &[u8])
+        -> Result<Vec<u8>,InvalidIvLen> {
+    encrypt(data, cipher, iv)
+}
+
+// Encrypts or decrypts in place
+pub fn apply_keystream<C: Cipher>(data: &mut [u8], cipher: &C, iv: &[u8])
+        -> Result<(),InvalidIvLen> {
+    let mut stream = C::to_block(iv).map_err(|_| InvalidIvLen)?;
+
+    for block in data.chunks_mut(C::BLOCK_SIZE) {
+        cipher.encrypt_block(&mut stream);
+        utils::xor_slice(block, stream.as_ref());
+    }
+
+    Ok(())
+}
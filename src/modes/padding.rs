@@ -0,0 +1,153 @@
+use crate::error::{InvalidDataLen,InvalidPadding};
+use crate::utils;
+
+
+pub trait Padding {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen>;
+
+    // Data length must be a multiple of the block size
+    fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], InvalidPadding>;
+}
+
+// Each padding byte is the padding length
+pub struct Pkcs7;
+
+// Zero bytes and the padding length as the last byte
+pub struct AnsiX923;
+
+// Random bytes and the padding length as the last byte
+pub struct Iso10126;
+
+// A single 0x80 byte followed by zero bytes
+pub struct Iso7816;
+
+// Zero bytes up to the block size, ambiguous if data ends with zero bytes
+pub struct ZeroPadding;
+
+// Data length must already be a multiple of the block size
+pub struct NoPadding;
+
+
+fn padding_size(data: &[u8], block_size: usize) -> usize {
+    block_size - data.len() % block_size
+}
+
+// Padding size stored in the last byte, between 1 and the block size
+fn padding_size_byte(data: &[u8], block_size: usize)
+        -> Result<usize, InvalidPadding> {
+    let padding_size = match data.last() {
+        Some(&last) => last as usize,
+        None => return Err(InvalidPadding),
+    };
+    if padding_size == 0 || padding_size > block_size
+            || padding_size > data.len() {
+        return Err(InvalidPadding);
+    }
+    Ok(padding_size)
+}
+
+impl Padding for Pkcs7 {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen> {
+        let padding_size = padding_size(data, block_size);
+        let mut padded: Vec<u8> = data.to_vec();
+        padded.resize(data.len() + padding_size, padding_size as u8);
+        Ok(padded)
+    }
+
+    fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], InvalidPadding> {
+        let padding_size = padding_size_byte(data, block_size)?;
+        let (unpadded, padding) = data.split_at(data.len() - padding_size);
+        if padding.iter().any(|&x| x as usize != padding_size) {
+            return Err(InvalidPadding);
+        }
+        Ok(unpadded)
+    }
+}
+
+impl Padding for AnsiX923 {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen> {
+        let padding_size = padding_size(data, block_size);
+        let mut padded: Vec<u8> = data.to_vec();
+        padded.resize(data.len() + padding_size - 1, 0);
+        padded.push(padding_size as u8);
+        Ok(padded)
+    }
+
+    fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], InvalidPadding> {
+        let padding_size = padding_size_byte(data, block_size)?;
+        let (unpadded, padding) = data.split_at(data.len() - padding_size);
+        if padding[..padding_size-1].iter().any(|&x| x != 0) {
+            return Err(InvalidPadding);
+        }
+        Ok(unpadded)
+    }
+}
+
+impl Padding for Iso10126 {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen> {
+        let padding_size = padding_size(data, block_size);
+        let mut padded: Vec<u8> = data.to_vec();
+        padded.extend(utils::random_bytes(padding_size - 1));
+        padded.push(padding_size as u8);
+        Ok(padded)
+    }
+
+    fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], InvalidPadding> {
+        let padding_size = padding_size_byte(data, block_size)?;
+        Ok(&data[..data.len()-padding_size])
+    }
+}
+
+impl Padding for Iso7816 {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen> {
+        let padding_size = padding_size(data, block_size);
+        let mut padded: Vec<u8> = data.to_vec();
+        padded.push(0x80);
+        padded.resize(data.len() + padding_size, 0);
+        Ok(padded)
+    }
+
+    fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], InvalidPadding> {
+        let last_block_start = data.len().saturating_sub(block_size);
+        for i in (last_block_start..data.len()).rev() {
+            match data[i] {
+                0x00 => continue,
+                0x80 => return Ok(&data[..i]),
+                _ => break,
+            }
+        }
+        Err(InvalidPadding)
+    }
+}
+
+impl Padding for ZeroPadding {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen> {
+        let padding_size = padding_size(data, block_size) % block_size;
+        let mut padded: Vec<u8> = data.to_vec();
+        padded.resize(data.len() + padding_size, 0);
+        Ok(padded)
+    }
+
+    fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], InvalidPadding> {
+        let last_block_start = data.len().saturating_sub(block_size);
+        let mut end = data.len();
+        while end > last_block_start && data[end-1] == 0 {
+            end -= 1;
+        }
+        Ok(&data[..end])
+    }
+}
+
+impl Padding for NoPadding {
+    fn pad(data: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidDataLen> {
+        if !data.len().is_multiple_of(block_size) {
+            return Err(InvalidDataLen);
+        }
+        Ok(data.to_vec())
+    }
+
+    fn unpad(data: &[u8], _block_size: usize)
+            -> Result<&[u8], InvalidPadding> {
+        Ok(data)
+    }
+}
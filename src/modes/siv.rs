@@ -0,0 +1,100 @@
+use crate::cipher::Cipher;
+use crate::error::{AeadError,AuthenticationFailed,InvalidDataLen,
+                   InvalidKeyLen,KeyError};
+use crate::mac::{Mac,Cmac,double};
+use crate::utils;
+use super::ctr;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const IV_SIZE: usize = 16;
+
+// S2V accepts at most 127 components including the plaintext
+const MAX_COMPONENTS: usize = 126;
+
+
+// RFC 5297, the key is the S2V (CMAC) key followed by the CTR key. The
+// output is the synthetic IV followed by the ciphertext. For nonce based
+// encryption the nonce is passed as the last associated data component.
+// Works with any 128 bit block cipher
+pub struct Siv<C> where C: Cipher<Block = Block> {
+    cmac: Cmac<C>,
+    cipher: C,
+}
+
+type Block = [u8; BLOCK_SIZE];
+
+impl<C> Siv<C> where C: Cipher<Block = Block> {
+    pub fn new(key: &[u8]) -> Result<Self, KeyError> {
+        if !key.len().is_multiple_of(2) {
+            return Err(InvalidKeyLen.into());
+        }
+        let (mac_key, key) = key.split_at(key.len() / 2);
+        let cmac = Cmac::<C>::new(mac_key)?;
+        let cipher = C::new(key)?;
+        Ok(Self { cmac, cipher })
+    }
+
+    pub fn encrypt(&mut self, data: &[u8], aad: &[&[u8]])
+            -> Result<Vec<u8>, AeadError> {
+        let v = self.s2v(aad, data)?;
+        let mut encrypted = v.to_vec();
+        encrypted.extend(ctr::keystream_xor(data, &self.cipher, counter(&v),
+                                            0));
+        Ok(encrypted)
+    }
+
+    pub fn decrypt(&mut self, data: &[u8], aad: &[&[u8]])
+            -> Result<Vec<u8>, AeadError> {
+        let (v, data) = data.split_first_chunk::<IV_SIZE>()
+            .ok_or(InvalidDataLen)?;
+        let decrypted = ctr::keystream_xor(data, &self.cipher, counter(v), 0);
+
+        let expected_v = self.s2v(aad, &decrypted)?;
+        if !utils::constant_time_eq(&expected_v, v) {
+            return Err(AuthenticationFailed.into());
+        }
+        Ok(decrypted)
+    }
+
+    fn s2v(&mut self, aad: &[&[u8]], data: &[u8])
+            -> Result<Block, AeadError> {
+        if aad.len() > MAX_COMPONENTS {
+            return Err(InvalidDataLen.into());
+        }
+
+        self.cmac.update(&[0; BLOCK_SIZE]);
+        let mut d = self.cmac.generate_block();
+        for component in aad {
+            double(&mut d);
+            self.cmac.update(component);
+            utils::xor_slice(&mut d, &self.cmac.generate_block());
+        }
+
+        if data.len() >= BLOCK_SIZE {
+            // XOR onto the end of the last component
+            let (head, tail) = data.split_at(data.len() - BLOCK_SIZE);
+            utils::xor_slice(&mut d, tail);
+            self.cmac.update(head);
+            self.cmac.update(&d);
+        } else {
+            double(&mut d);
+            let mut t = [0; BLOCK_SIZE];
+            t[..data.len()].copy_from_slice(data);
+            t[data.len()] = 0x80;
+            utils::xor_slice(&mut d, &t);
+            self.cmac.update(&d);
+        }
+        Ok(self.cmac.generate_block())
+    }
+}
+
+
+// The 31st and 63rd bits are cleared so CTR implementations can use 32 and
+// 64 bit counters
+fn counter(v: &Block) -> Block {
+    let mut q = *v;
+    q[8] &= 0x7f;
+    q[12] &= 0x7f;
+    q
+}
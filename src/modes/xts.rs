@@ -0,0 +1,140 @@
+use crate::cipher::Cipher;
+use crate::error::{InvalidDataLen,InvalidIvLen,InvalidKeyLen,KeyError,
+                   ModeError,WeakKey};
+use crate::utils;
+
+
+pub const BLOCK_SIZE: usize = 16;
+pub const TWEAK_SIZE: usize = 16;
+
+
+// IEEE 1619 XTS with any 128 bit block cipher, the key is the data key
+// followed by the tweak key
+pub struct Xts<C> where C: Cipher<Block = Block> {
+    cipher: C,
+    tweak_cipher: C,
+}
+
+type Block = [u8; BLOCK_SIZE];
+
+impl<C> Xts<C> where C: Cipher<Block = Block> {
+    pub fn new(key: &[u8]) -> Result<Self, KeyError> {
+        if !key.len().is_multiple_of(2) {
+            return Err(InvalidKeyLen.into());
+        }
+        let (key1, key2) = key.split_at(key.len() / 2);
+        if utils::constant_time_eq(key1, key2) {
+            return Err(WeakKey.into());
+        }
+
+        let cipher = C::new(key1)?;
+        let tweak_cipher = C::new(key2)?;
+        Ok(Self { cipher, tweak_cipher })
+    }
+
+    // The sector number is encoded as a little endian tweak
+    pub fn encrypt_sector(&self, data: &[u8], sector: u128)
+            -> Result<Vec<u8>, ModeError> {
+        self.encrypt(data, &sector.to_le_bytes())
+    }
+
+    pub fn decrypt_sector(&self, data: &[u8], sector: u128)
+            -> Result<Vec<u8>, ModeError> {
+        self.decrypt(data, &sector.to_le_bytes())
+    }
+
+    pub fn encrypt(&self, data: &[u8], tweak: &[u8])
+            -> Result<Vec<u8>, ModeError> {
+        let (mut t, full_blocks, partial) = self.init(data, tweak)?;
+        let mut encrypted = data.to_vec();
+        let full_len = full_blocks * BLOCK_SIZE;
+
+        for block in encrypted[..full_len].as_chunks_mut().0 {
+            self.xex(block, &t, true);
+            mult_alpha(&mut t);
+        }
+
+        if partial > 0 {
+            // Ciphertext stealing from the last full block
+            let last_start = full_len - BLOCK_SIZE;
+            let mut block = [0; BLOCK_SIZE];
+            block[..partial].copy_from_slice(&data[full_len..]);
+            block[partial..].copy_from_slice(
+                &encrypted[last_start+partial..full_len]);
+            encrypted.copy_within(last_start..last_start+partial, full_len);
+            self.xex(&mut block, &t, true);
+            encrypted[last_start..full_len].copy_from_slice(&block);
+        }
+
+        Ok(encrypted)
+    }
+
+    pub fn decrypt(&self, data: &[u8], tweak: &[u8])
+            -> Result<Vec<u8>, ModeError> {
+        let (mut t, full_blocks, partial) = self.init(data, tweak)?;
+        let mut decrypted = data.to_vec();
+
+        // With stealing the last full block is processed with the next tweak
+        let regular_blocks = if partial > 0 { full_blocks - 1 } else {
+            full_blocks
+        };
+        let regular_len = regular_blocks * BLOCK_SIZE;
+        for block in decrypted[..regular_len].as_chunks_mut().0 {
+            self.xex(block, &t, false);
+            mult_alpha(&mut t);
+        }
+
+        if partial > 0 {
+            let mut next_t = t;
+            mult_alpha(&mut next_t);
+            let mut stolen = [0; BLOCK_SIZE];
+            stolen.copy_from_slice(&data[regular_len..regular_len+BLOCK_SIZE]);
+            self.xex(&mut stolen, &next_t, false);
+
+            let mut block = [0; BLOCK_SIZE];
+            block[..partial].copy_from_slice(&data[data.len()-partial..]);
+            block[partial..].copy_from_slice(&stolen[partial..]);
+            self.xex(&mut block, &t, false);
+            decrypted[regular_len..regular_len+BLOCK_SIZE]
+                .copy_from_slice(&block);
+            decrypted[regular_len+BLOCK_SIZE..]
+                .copy_from_slice(&stolen[..partial]);
+        }
+
+        Ok(decrypted)
+    }
+
+    // Returns the initial tweak value, full blocks and partial block size
+    fn init(&self, data: &[u8], tweak: &[u8])
+            -> Result<(Block, usize, usize), ModeError> {
+        let mut t: [u8; TWEAK_SIZE] = tweak.try_into()
+            .map_err(|_| InvalidIvLen)?;
+        if data.len() < BLOCK_SIZE {
+            return Err(InvalidDataLen.into());
+        }
+        self.tweak_cipher.encrypt_block(&mut t);
+        Ok((t, data.len() / BLOCK_SIZE, data.len() % BLOCK_SIZE))
+    }
+
+    // C = E(P xor T) xor T, in place
+    fn xex(&self, block: &mut Block, t: &Block, encrypting: bool) {
+        utils::xor_slice(block, t);
+        if encrypting {
+            self.cipher.encrypt_block(block);
+        } else {
+            self.cipher.decrypt_block(block);
+        }
+        utils::xor_slice(block, t);
+    }
+}
+
+
+// Multiplication by the primitive element in GF(2^128), little endian
+fn mult_alpha(t: &mut Block) {
+    let value = u128::from_le_bytes(*t);
+    let mut doubled = value << 1;
+    if value >> 127 == 1 {
+        doubled ^= 0x87;
+    }
+    *t = doubled.to_le_bytes();
+}
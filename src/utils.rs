@@ -1,10 +1,12 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher,Hasher};
 use std::num::ParseIntError;
 
 
 #[allow(dead_code)] // Use for debug
 pub fn print_hex(data: &[u8]) {
-    for i in 0..data.len() {
-        print!("{:02x}", data[i]);
+    for (i, byte) in data.iter().enumerate() {
+        print!("{:02x}", byte);
         if (i+1) % 4 == 0 {
             print!(" ");
         }
@@ -18,6 +20,21 @@ pub fn xor_slice(a: &mut [u8], b: &[u8]) {
     }
 }
 
+// Randomly seeded SipHash output, NOT suitable for key material
+pub fn random_bytes(len: usize) -> Vec<u8> {
+    let state = RandomState::new();
+    let mut bytes = Vec::with_capacity(len + 8);
+    let mut counter: u64 = 0;
+    while bytes.len() < len {
+        let mut hasher = state.build_hasher();
+        hasher.write_u64(counter);
+        bytes.extend_from_slice(&hasher.finish().to_le_bytes());
+        counter += 1;
+    }
+    bytes.truncate(len);
+    bytes
+}
+
 
 #[allow(dead_code)] // Use for tests
 pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
@@ -30,3 +47,15 @@ pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
     }
     Ok(bytes)
 }
+
+// Comparison time does not depend on the position of the first difference
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}